@@ -0,0 +1,45 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use notestem::Analyzer;
+
+const SYLLABLES: [&str; 16] = [
+    "ка", "ро", "ми", "ст", "ло", "не", "ва", "ти",
+    "пра", "до", "зе", "ку", "ры", "ше", "ог", "ль",
+];
+
+/// Deterministic pseudo-random words, so runs are comparable
+fn words(count: usize, seed: u64) -> Vec<String> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (state >> 33) as usize
+    };
+
+    (0..count).map(|_| (0..2 + next() % 4).map(|_| SYLLABLES[next() % SYLLABLES.len()])
+                                           .collect::<String>())
+              .collect()
+}
+
+fn exclusion(c: &mut Criterion) {
+    let corpus = words(20_000, 1).join(" ");
+
+    let mut group = c.benchmark_group("exclusion");
+    group.throughput(Throughput::Bytes(corpus.len() as u64));
+    group.sample_size(10);
+
+    for exclusions in [0, 500, 5_000] {
+        let exclude = words(exclusions, 2);
+        group.bench_with_input(BenchmarkId::from_parameter(exclusions), &exclude, |b, exclude| {
+            b.iter(|| {
+                let mut analyzer = Analyzer::builder().exclude(exclude.clone()).build();
+                analyzer.add_document("corpus.txt", corpus.as_str());
+                analyzer.report()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, exclusion);
+criterion_main!(benches);
@@ -0,0 +1,310 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use regex::Regex;
+
+use crate::{
+    context::Context,
+    dict::Dict,
+    exclusions::{Exclusions, Pattern},
+    lang::{AlphabetDetector, Detector, Language},
+    markup::Extracted,
+    normalizer::{Normalizer, SnowballStemmer},
+    report::Report,
+    stopwords,
+    tokenizer::Tokenizer,
+};
+
+/// Исключения одного языка, подготовленные для быстрой проверки
+#[derive(Default)]
+struct Excluded {
+    stems: HashSet<String>,
+    forms: HashSet<String>,
+    patterns: Vec<Regex>,
+}
+
+impl Excluded {
+    fn contains(&self, word: &str, stem: &str) -> bool {
+        self.stems.contains(stem) ||
+        self.forms.contains(word) ||
+        self.patterns.iter().any(|re| re.is_match(word))
+    }
+}
+
+/// Настройки анализатора; создаётся через [`Analyzer::builder`]
+pub struct AnalyzerBuilder {
+    words: usize,
+    filenum: usize,
+    length: usize,
+    languages: Vec<Language>,
+    detector: Option<Box<dyn Detector>>,
+    normalizer: Option<Box<dyn Normalizer>>,
+    exclusions: Exclusions,
+    stopwords: bool,
+    context: Option<usize>,
+}
+
+impl Default for AnalyzerBuilder {
+    fn default() -> Self {
+        Self {
+            words: 2,
+            filenum: 2,
+            length: 6,
+            languages: Language::ALL.to_vec(),
+            detector: None,
+            normalizer: None,
+            exclusions: Exclusions::new(),
+            stopwords: true,
+            context: None,
+        }
+    }
+}
+
+impl AnalyzerBuilder {
+    /// Наименьшее число повторов слова
+    pub fn words(mut self, words: usize) -> Self {
+        self.words = words;
+        self
+    }
+
+    /// В отчёт попадают слова, встречающиеся более чем в `filenum` документах
+    pub fn filenum(mut self, filenum: usize) -> Self {
+        self.filenum = filenum;
+        self
+    }
+
+    /// В отчёт попадают слова, основа которых длиннее `length` символов
+    pub fn length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
+    }
+
+    /// Активные языки в порядке приоритета при определении языка слова
+    pub fn languages(mut self, languages: Vec<Language>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    /// Заменяет определение языка по алфавиту на собственное
+    pub fn detector(mut self, detector: Box<dyn Detector>) -> Self {
+        self.detector = Some(detector);
+        self
+    }
+
+    /// Заменяет стемминг Snowball другим способом нормализации слов
+    pub fn normalizer(mut self, normalizer: Box<dyn Normalizer>) -> Self {
+        self.normalizer = Some(normalizer);
+        self
+    }
+
+    /// Слова, которые следует исключить из отчёта вместе со всеми их формами
+    pub fn exclude(mut self, exclude: Vec<String>) -> Self {
+        for word in exclude {
+            self.exclusions.add(None, Pattern::Stemmed(word.to_lowercase()));
+        }
+        self
+    }
+
+    /// Добавляет исключения, например прочитанные из файла через [`Exclusions::load`]
+    pub fn exclusions(mut self, exclusions: Exclusions) -> Self {
+        self.exclusions.append(exclusions);
+        self
+    }
+
+    /// Исключать ли встроенные стоп-слова каждого языка (по умолчанию да)
+    pub fn stopwords(mut self, enabled: bool) -> Self {
+        self.stopwords = enabled;
+        self
+    }
+
+    /// Сохранять тексты документов, чтобы показывать `width` слов контекста вокруг вхождений
+    pub fn context(mut self, width: usize) -> Self {
+        self.context = Some(width);
+        self
+    }
+
+    pub fn build(self) -> Analyzer {
+        let detector = self.detector
+                           .unwrap_or_else(|| Box::new(AlphabetDetector::new(self.languages)));
+        let normalizer = self.normalizer
+                             .unwrap_or_else(|| Box::new(SnowballStemmer::new()));
+
+        Analyzer {
+            words: self.words,
+            filenum: self.filenum,
+            length: self.length,
+            detector,
+            normalizer,
+            exclusions: self.exclusions,
+            stopwords: self.stopwords,
+            excluded: HashMap::new(),
+            tokenizer: Tokenizer::new(),
+            dicts: BTreeMap::new(),
+            context: self.context.map(Context::new),
+        }
+    }
+}
+
+/// Собирает слова из документов и строит отчёт о повторах
+pub struct Analyzer {
+    words: usize,
+    filenum: usize,
+    length: usize,
+    detector: Box<dyn Detector>,
+    normalizer: Box<dyn Normalizer>,
+    exclusions: Exclusions,
+    stopwords: bool,
+    /// Нормализованные исключения и стоп-слова по языкам; заполняется при первой встрече языка
+    excluded: HashMap<Language, Excluded>,
+    tokenizer: Tokenizer,
+    dicts: BTreeMap<Language, Dict>,
+    context: Option<Context>,
+}
+
+impl Analyzer {
+    pub fn builder() -> AnalyzerBuilder {
+        AnalyzerBuilder::default()
+    }
+
+    /// Добавляет документ с именем `name`, под которым он появится в отчёте.
+    /// Вместо простого текста можно передать текст, извлечённый из размеченного
+    /// документа через [`Markup::extract`](crate::markup::Markup::extract).
+    pub fn add_document(&mut self, name: impl Into<String>, text: impl Into<Extracted>) {
+        self.add_file(vec![(name.into(), text.into())]);
+    }
+
+    /// Добавляет части одного входного файла, например главы или разделы, как
+    /// отдельные документы. Язык слов определяется по всему файлу сразу, поэтому
+    /// одно и то же слово в разных частях относится к одному языку.
+    pub fn add_file(&mut self, parts: Vec<(String, Extracted)>) {
+        let tokens = parts.iter()
+                          .map(|(_, text)| self.tokenizer.tokens(text.text()))
+                          .collect::<Vec<_>>();
+        let words = tokens.iter()
+                          .flatten()
+                          .map(|token| token.normalized())
+                          .collect::<Vec<_>>();
+        let mut languages = self.detector.detect_all(&words).into_iter();
+        let mut words = words.into_iter();
+
+        for ((name, _), tokens) in parts.iter().zip(&tokens) {
+            for token in tokens {
+                let (Some(w), Some(Some(lang))) = (words.next(), languages.next()) else {
+                    continue;
+                };
+                let stem = self.normalizer.normalize(&w, lang);
+
+                let excluded = self.excluded.entry(lang).or_insert_with(|| {
+                    let mut excluded = Excluded::default();
+                    for pattern in self.exclusions.for_language(lang) {
+                        match pattern {
+                            Pattern::Stemmed(word) => { excluded.stems.insert(self.normalizer.normalize(word, lang)); }
+                            Pattern::Exact(word) => { excluded.forms.insert(word.clone()); }
+                            Pattern::Regex(re) => excluded.patterns.push(re.clone()),
+                        }
+                    }
+                    if self.stopwords {
+                        excluded.stems.extend(stopwords::words(lang).map(|word| self.normalizer.normalize(word, lang)));
+                    }
+
+                    excluded
+                });
+                if excluded.contains(&w, &stem) {
+                    continue;
+                }
+
+                self.dicts.entry(lang).or_default().add(stem, w, name.clone(), token.offset);
+            }
+        }
+
+        if let Some(context) = &mut self.context {
+            for (name, text) in parts {
+                context.add(name, text);
+            }
+        }
+    }
+
+    /// Словарь основ для языка `lang`, если в документах встретились его слова
+    pub fn dict(&self, lang: Language) -> Option<&Dict> {
+        self.dicts.get(&lang)
+    }
+
+    /// Отбирает слова по заданным порогам и строит отчёт
+    pub fn report(self) -> Report {
+        let mut report = Report::new();
+        for (lang, dict) in self.dicts {
+            for (stem, entry) in dict.sort(self.words, self.filenum, self.length) {
+                report.add(lang, stem, &entry);
+            }
+        }
+        report.context = self.context;
+
+        report
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::lang::Language;
+    use super::Analyzer;
+
+    #[test]
+    fn analyze_documents() {
+        let mut analyzer = Analyzer::builder()
+                                    .words(2)
+                                    .filenum(1)
+                                    .length(3)
+                                    .exclude(vec!["документ".to_string()])
+                                    .build();
+        analyzer.add_document("a.md", "Повторы в документе: повтор, повторы. Documents repeat.");
+        analyzer.add_document("b.md", "Ещё один повтор в документах, document repeats.");
+
+        let report = analyzer.report();
+        let mut words = report.words.iter()
+                                    .map(|w| (w.language, w.word.as_str(), w.count, w.files.len()))
+                                    .collect::<Vec<_>>();
+        words.sort();
+
+        assert_eq!(words, vec![
+            (Language::English, "document", 2, 2),
+            (Language::English, "repeat", 2, 2),
+            (Language::Russian, "повтор", 4, 2),
+        ]);
+    }
+
+    #[test]
+    fn detect_language_per_file() {
+        let mut analyzer = Analyzer::builder()
+                                    .filenum(1)
+                                    .length(3)
+                                    .build();
+        analyzer.add_file(vec![
+            ("a.md#абзац 1".to_string(), "Players die often in games.".into()),
+            ("a.md#абзац 3".to_string(), "Players rarely win.".into()),
+        ]);
+
+        let report = analyzer.report();
+        let words = report.words.iter()
+                                .map(|w| (w.language, w.word.as_str(), w.count))
+                                .collect::<Vec<_>>();
+
+        assert_eq!(words, vec![(Language::English, "players", 2)]);
+    }
+
+    #[test]
+    fn builtin_stopwords() {
+        let text = "Который раз который день";
+        let count = |stopwords: bool| {
+            let mut analyzer = Analyzer::builder()
+                                        .filenum(0)
+                                        .length(0)
+                                        .stopwords(stopwords)
+                                        .build();
+            analyzer.add_document("a.md", text);
+            analyzer.report().words.len()
+        };
+
+        assert_eq!(count(true), 0);
+        assert_eq!(count(false), 1);
+    }
+}
@@ -0,0 +1,153 @@
+use std::{env, fs, path::{Path, PathBuf}};
+
+use clap::{parser::ValueSource, ArgMatches, Command};
+use serde::{de::DeserializeOwned, Serialize};
+use toml::{Table, Value};
+
+/// Имя файла конфигурации, который ищется в текущем каталоге и выше
+pub const CONFIG_FILENAME: &str = "notestem.toml";
+
+/// Параметры, значения которых — пути; относительные пути отсчитываются
+/// от каталога файла конфигурации
+const PATH_KEYS: [&str; 3] = ["exclude-file", "stopwords-file", "lemma-dict"];
+
+pub struct Config {
+    pub path: PathBuf,
+    defaults: Table,
+    profiles: Table,
+}
+
+/// Ищет `notestem.toml` в текущем каталоге и его родителях, затем
+/// `$XDG_CONFIG_HOME/notestem/config.toml` (по умолчанию `~/.config`)
+pub fn find() -> Option<PathBuf> {
+    let cwd = env::current_dir().ok()?;
+    if let Some(path) = cwd.ancestors().map(|dir| dir.join(CONFIG_FILENAME)).find(|p| p.is_file()) {
+        return Some(path);
+    }
+
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+                          .filter(|dir| !dir.is_empty())
+                          .map(PathBuf::from)
+                          .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    let path = config_home.join("notestem").join("config.toml");
+
+    path.is_file().then_some(path)
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Ошибка при открытии файла {}: {e}", path.display()))?;
+        let mut defaults: Table = text.parse().map_err(|e| format!("Ошибка в файле {}: {e}", path.display()))?;
+
+        let profiles = match defaults.remove("profiles") {
+            Some(Value::Table(profiles)) => profiles,
+            Some(_) => return Err(format!("Ошибка в файле {}: profiles должен быть таблицей", path.display())),
+            None => Table::new(),
+        };
+
+        Ok(Self { path: path.to_path_buf(), defaults, profiles })
+    }
+
+    /// Накладывает значения из файла и профиля `profile` на разобранные аргументы.
+    /// Параметры, явно заданные в командной строке, не переопределяются.
+    pub fn merge<T: Serialize + DeserializeOwned>(&self, args: &T, command: &Command, matches: &ArgMatches,
+                                                    profile: Option<&str>) -> Result<T, String> {
+        let mut merged = Table::try_from(args).map_err(|e| e.to_string())?;
+
+        let mut layers = vec![&self.defaults];
+        if let Some(name) = profile {
+            match self.profiles.get(name) {
+                Some(Value::Table(table)) => layers.push(table),
+                _ => return Err(format!("Профиль \"{name}\" не найден в {}", self.path.display())),
+            }
+        }
+
+        // Typos are reported even in profiles that were not selected
+        let all = std::iter::once(("", &self.defaults))
+                      .chain(self.profiles.iter().filter_map(|(name, p)| p.as_table().map(|p| (name.as_str(), p))));
+        for (name, layer) in all {
+            if let Some(key) = layer.keys().find(|key| !command.get_arguments().any(|arg| arg.get_id() == id(key).as_str())) {
+                let place = if name.is_empty() { String::new() } else { format!(" (профиль {name})") };
+                return Err(format!("Неизвестный параметр \"{key}\"{place} в {}", self.path.display()));
+            }
+        }
+
+        for layer in layers {
+            for (key, value) in layer {
+                if matches.value_source(&id(key)) == Some(ValueSource::CommandLine) {
+                    continue;
+                }
+
+                merged.insert(key.clone(), self.resolve(key, value.clone()));
+            }
+        }
+
+        merged.try_into().map_err(|e: toml::de::Error| format!("Ошибка в файле {}: {}", self.path.display(), e.message()))
+    }
+
+    fn resolve(&self, key: &str, value: Value) -> Value {
+        match (PATH_KEYS.contains(&key), &value, self.path.parent()) {
+            (true, Value::String(path), Some(dir)) => Value::String(dir.join(path).display().to_string()),
+            _ => value,
+        }
+    }
+}
+
+/// Идентификатор параметра clap по ключу файла конфигурации
+fn id(key: &str) -> String {
+    key.replace('-', "_")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use clap::{CommandFactory, FromArgMatches, Parser};
+    use serde::{Deserialize, Serialize};
+
+    use super::Config;
+
+    #[derive(Debug, Parser, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    struct Args {
+        #[arg(short, long, default_value_t = 2)]
+        words: usize,
+        #[arg(short, long, default_value_t = 6)]
+        length: usize,
+        #[arg(long)]
+        exclude_file: Option<String>,
+    }
+
+    #[test]
+    fn merge_defaults_profile_and_command_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notestem.toml");
+        fs::write(&path, "\
+words = 3
+length = 7
+
+[profiles.thesis]
+length = 9
+exclude-file = \"stop.txt\"
+").unwrap();
+        let config = Config::load(&path).unwrap();
+
+        let command = Args::command();
+        let matches = command.clone().get_matches_from(["notestem", "-w", "5"]);
+        let args = Args::from_arg_matches(&matches).unwrap();
+
+        let merged = config.merge(&args, &command, &matches, None).unwrap();
+        assert_eq!((merged.words, merged.length, merged.exclude_file), (5, 7, None));
+
+        let merged = config.merge(&args, &command, &matches, Some("thesis")).unwrap();
+        assert_eq!((merged.words, merged.length), (5, 9));
+        assert_eq!(merged.exclude_file, Some(dir.path().join("stop.txt").display().to_string()));
+
+        assert!(config.merge(&args, &command, &matches, Some("blog")).is_err());
+
+        fs::write(&path, "[profiles.draft]\nwordz = 1\n").unwrap();
+        let config = Config::load(&path).unwrap();
+        assert!(config.merge(&args, &command, &matches, None).unwrap_err().contains("wordz"));
+    }
+}
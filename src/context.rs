@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use crate::{markup::{Extracted, Unit}, tokenizer::Tokenizer};
+
+/// Текст документа вместе с разметкой, нужной для поиска контекста вхождений
+#[derive(Debug)]
+struct Document {
+    extracted: Extracted,
+    /// Границы слов в байтах
+    words: Vec<(usize, usize)>,
+}
+
+/// Фрагмент текста вокруг вхождения слова
+#[derive(Debug, PartialEq, Eq)]
+pub struct Snippet {
+    /// В чём указан номер `line`: в строках или, например, в абзацах
+    pub unit: Unit,
+    pub line: usize,
+    pub column: usize,
+    pub before: String,
+    pub word: String,
+    pub after: String,
+}
+
+/// Хранит тексты документов, чтобы показывать вхождения в стиле KWIC
+#[derive(Debug)]
+pub struct Context {
+    width: usize,
+    documents: HashMap<String, Document>,
+}
+
+impl Context {
+    /// `width` — число слов контекста с каждой стороны от вхождения
+    pub fn new(width: usize) -> Self {
+        Self { width, documents: HashMap::new() }
+    }
+
+    pub fn add(&mut self, name: String, extracted: Extracted) {
+        let words = Tokenizer::new().tokens(extracted.text())
+                                    .iter()
+                                    .map(|t| (t.offset, t.offset + t.text.len()))
+                                    .collect();
+
+        self.documents.insert(name, Document { extracted, words });
+    }
+
+    /// Вхождение слова, начинающегося со смещения `offset` в извлечённом тексте документа `name`.
+    /// Строка и столбец указывают на исходный документ.
+    pub fn snippet(&self, name: &str, offset: usize) -> Option<Snippet> {
+        let doc = self.documents.get(name)?;
+        let index = doc.words.binary_search_by_key(&offset, |&(start, _)| start).ok()?;
+        let (start, end) = doc.words[index];
+        let text = doc.extracted.text();
+
+        let (line, column) = doc.extracted.locate(offset);
+
+        let first = doc.words[index.saturating_sub(self.width)].0;
+        let last = doc.words[(index + self.width).min(doc.words.len() - 1)].1;
+
+        Some(Snippet {
+            unit: doc.extracted.unit(),
+            line,
+            column,
+            before: flatten(&text[first..start]),
+            word: text[start..end].to_string(),
+            after: flatten(&text[end..last]),
+        })
+    }
+}
+
+fn flatten(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::markup::Unit;
+    use super::{Context, Snippet};
+
+    #[test]
+    fn snippet_location_and_words() {
+        let mut context = Context::new(2);
+        context.add("a.md".to_string(), "Один два три.\nЧетыре  пять\tшесть семь".into());
+
+        let offset = "Один два три.\nЧетыре  ".len();
+        assert_eq!(context.snippet("a.md", offset), Some(Snippet {
+            unit: Unit::Line,
+            line: 2,
+            column: 9,
+            before: "три. Четыре".to_string(),
+            word: "пять".to_string(),
+            after: "шесть семь".to_string(),
+        }));
+        assert_eq!(context.snippet("a.md", 0).map(|s| (s.line, s.before)), Some((1, String::new())));
+        assert_eq!(context.snippet("b.md", 0), None);
+    }
+}
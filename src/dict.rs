@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Entry {
+    amount: usize,
+    /// Смещения вхождений слова в байтах по каждому файлу
+    contained_in: HashMap<String, Vec<usize>>,
+    /// Встреченные словоформы и их частоты
+    forms: HashMap<String, usize>,
+}
+
+impl Entry {
+    /// Общее число вхождений
+    pub fn amount(&self) -> usize {
+        self.amount
+    }
+
+    /// Смещения вхождений в байтах по каждому файлу
+    pub fn contained_in(&self) -> &HashMap<String, Vec<usize>> {
+        &self.contained_in
+    }
+
+    /// Число вхождений в файле `fname`
+    pub fn count_in(&self, fname: &str) -> usize {
+        self.contained_in.get(fname).map_or(0, Vec::len)
+    }
+
+    /// Встреченные словоформы и их частоты
+    pub fn forms(&self) -> &HashMap<String, usize> {
+        &self.forms
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Dict {
+    hashmap: HashMap<String, Entry>,
+}
+
+impl Dict {
+    pub fn new() -> Self {
+        Self { hashmap: HashMap::new() }
+    }
+
+    pub fn add(&mut self, word: String, form: String, fname: String, position: usize) -> usize {
+        let entry = self.hashmap.entry(word).or_default();
+        entry.amount += 1;
+        entry.contained_in.entry(fname).or_default().push(position);
+        *entry.forms.entry(form).or_insert(0) += 1;
+
+        entry.amount
+    }
+
+    pub fn get(&self, word: &str) -> Option<&Entry> {
+        self.hashmap.get(word)
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashmap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashmap.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Entry)> {
+        self.hashmap.iter()
+    }
+
+    /// Отбирает слова по порогам и сортирует их по убыванию числа вхождений,
+    /// а при равном числе — по основе, чтобы выдача не зависела от порядка в хеш-таблице
+    pub fn sort(self, words: usize, filenum: usize, length: usize) -> Vec<(String, Entry)> {
+        let mut v = self.hashmap.into_iter()
+                    .filter(|(w, entry)|
+                            entry.contained_in.len() > filenum &&
+                            entry.amount >= words &&
+                            w.graphemes(true).count() > length)
+                    .collect::<Vec<(String, Entry)>>();
+
+        v.sort_by(|a, b| b.1.amount.cmp(&a.1.amount).then_with(|| a.0.cmp(&b.0)));
+
+        v
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{Dict, Entry};
+
+    #[test]
+    fn sort_vec() {
+        let mut st = Dict::new();
+        st.add("foobar".to_string(), "foobar".to_string(), "a.txt".to_string(), 0);
+        st.add("bazquux".to_string(), "bazquux".to_string(), "b.txt".to_string(), 0);
+        st.add("foobar".to_string(), "foobars".to_string(), "c.txt".to_string(), 7);
+        st.add("foobar".to_string(), "foobars".to_string(), "c.txt".to_string(), 14);
+
+        assert_eq!(st.get("foobar").map(|e| e.count_in("c.txt")), Some(2));
+        assert_eq!(
+            st.sort(1, 0, 0),
+            vec![
+                ("foobar".to_string(), Entry {
+                    amount: 3,
+                    contained_in: HashMap::from([("a.txt".to_string(), vec![0]), ("c.txt".to_string(), vec![7, 14])]),
+                    forms: HashMap::from([("foobar".to_string(), 1), ("foobars".to_string(), 2)]),
+                }),
+                ("bazquux".to_string(), Entry {
+                    amount: 1,
+                    contained_in: HashMap::from([("b.txt".to_string(), vec![0])]),
+                    forms: HashMap::from([("bazquux".to_string(), 1)]),
+                }),
+            ])
+    }
+
+    #[test]
+    fn sort_ties_by_stem() {
+        let mut st = Dict::new();
+        for word in ["delta", "alpha", "charlie", "bravo", "echo"] {
+            st.add(word.to_string(), word.to_string(), "a.txt".to_string(), 0);
+        }
+        st.add("echo".to_string(), "echo".to_string(), "a.txt".to_string(), 5);
+
+        let words = st.sort(1, 0, 0).into_iter().map(|(w, _)| w).collect::<Vec<_>>();
+        assert_eq!(words, vec!["echo", "alpha", "bravo", "charlie", "delta"]);
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+
+use encoding_rs::{Encoding, KOI8_R, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1251, WINDOWS_1252};
+
+/// Сколько байт от начала файла просматривается при проверке на двоичность
+const BINARY_SAMPLE: usize = 8192;
+
+/// Однобайтовые кириллические кодировки, между которыми выбирает эвристика
+const CYRILLIC: [&Encoding; 2] = [WINDOWS_1251, KOI8_R];
+
+/// Частоты строчных букв русского языка на тысячу букв
+const FREQUENCIES: [(char, u32); 33] = [
+    ('о', 110), ('е', 85), ('а', 80), ('и', 74), ('н', 67), ('т', 63), ('с', 55), ('р', 47), ('в', 45),
+    ('л', 44), ('к', 35), ('м', 32), ('д', 30), ('п', 28), ('у', 26), ('я', 20), ('ы', 19), ('ь', 17),
+    ('г', 17), ('з', 16), ('б', 16), ('ч', 14), ('й', 12), ('х', 10), ('ж', 9), ('ш', 7), ('ю', 6),
+    ('ц', 5), ('щ', 4), ('э', 3), ('ф', 2), ('ъ', 1), ('ё', 1),
+];
+
+/// Признак, по которому файл сочтён двоичным
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binary {
+    NulBytes,
+    ControlCharacters,
+}
+
+impl fmt::Display for Binary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binary::NulBytes => write!(f, "двоичный файл: содержит нулевые байты"),
+            Binary::ControlCharacters => write!(f, "двоичный файл: слишком много управляющих символов"),
+        }
+    }
+}
+
+impl std::error::Error for Binary {}
+
+/// Проверяет, не двоичные ли данные перед нами: текст не содержит нулевых байтов
+/// (кроме UTF-16) и почти не содержит управляющих символов, кроме пробельных
+pub fn binary(bytes: &[u8]) -> Option<Binary> {
+    let sample = &bytes[..bytes.len().min(BINARY_SAMPLE)];
+    if Encoding::for_bom(sample).is_some() || detect_utf16(sample).is_some() {
+        return None;
+    }
+
+    let control = sample.iter()
+                        .filter(|&&b| (b < 0x20 && !b"\t\n\r\x0c\x1b".contains(&b)) || b == 0x7f)
+                        .count();
+    if sample.contains(&0) {
+        Some(Binary::NulBytes)
+    } else if control * 10 > sample.len() {
+        Some(Binary::ControlCharacters)
+    } else {
+        None
+    }
+}
+
+/// Определяет кодировку текста. Сначала проверяется BOM, затем чередование байтов,
+/// характерное для UTF-16 без BOM, затем корректность UTF-8. Остальные тексты
+/// считаются однобайтовыми: из Windows-1251 и KOI8-R выбирается та, в которой
+/// текст больше похож на русский; если кириллицы нет ни в одной, — Windows-1252.
+pub fn detect(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    if let Some(encoding) = detect_utf16(bytes) {
+        return encoding;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+
+    CYRILLIC.into_iter()
+            .map(|encoding| (encoding, score(&encoding.decode_without_bom_handling(bytes).0)))
+            .filter(|&(_, score)| score > 0)
+            .max_by_key(|&(_, score)| score)
+            .map_or(WINDOWS_1252, |(encoding, _)| encoding)
+}
+
+/// Декодирует текст в кодировке `encoding` или, если она не задана, в определённой
+/// через [`detect`]. BOM отбрасывается; некорректные последовательности заменяются на U+FFFD.
+pub fn decode(bytes: &[u8], encoding: Option<&'static Encoding>) -> (String, &'static Encoding) {
+    let encoding = encoding.unwrap_or_else(|| detect(bytes));
+    let bytes = match Encoding::for_bom(bytes) {
+        Some((bom, length)) if bom == encoding => &bytes[length..],
+        _ => bytes,
+    };
+
+    (encoding.decode_without_bom_handling(bytes).0.into_owned(), encoding)
+}
+
+/// UTF-16 без BOM: в латинском и кириллическом тексте каждый второй байт
+/// равен 0x00 или 0x04 (старший байт символов U+0000–U+00FF и U+0400–U+04FF)
+fn detect_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let pairs = bytes.len() / 2;
+    if pairs == 0 {
+        return None;
+    }
+    let high = |parity: usize| bytes.iter().skip(parity).step_by(2).filter(|&&b| b == 0x00 || b == 0x04).count();
+    let (even, odd) = (high(0), high(1));
+
+    // The other half must mostly hold other bytes, otherwise this is binary data
+    if odd * 4 > pairs * 3 && even * 4 < pairs {
+        Some(UTF_16LE)
+    } else if even * 4 > pairs * 3 && odd * 4 < pairs {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Насколько текст похож на русский: строчные буквы весят по своей частоте,
+/// заглавные — в десять раз меньше, так как в неверной кодировке регистр путается
+fn score(text: &str) -> u32 {
+    text.chars()
+        .filter_map(|c| {
+            let lower = c.to_lowercase().next()?;
+            let &(_, frequency) = FREQUENCIES.iter().find(|&&(letter, _)| letter == lower)?;
+            Some(if c == lower { frequency * 10 } else { frequency })
+        })
+        .sum()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::{KOI8_R, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1251};
+
+    use super::{binary, decode, detect, Binary};
+
+    const TEXT: &str = "Повторение - мать учения, говорили нам в школе.";
+
+    #[test]
+    fn detect_cyrillic_encodings() {
+        assert_eq!(detect(TEXT.as_bytes()), UTF_8);
+        assert_eq!(detect(&WINDOWS_1251.encode(TEXT).0), WINDOWS_1251);
+        assert_eq!(detect(&KOI8_R.encode(TEXT).0), KOI8_R);
+        assert_eq!(decode(&KOI8_R.encode(TEXT).0, None).0, TEXT);
+    }
+
+    #[test]
+    fn detect_utf16() {
+        let le = "Plain text".encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<u8>>();
+        let be = "Plain text".encode_utf16().flat_map(u16::to_be_bytes).collect::<Vec<u8>>();
+        let with_bom = [&[0xFF, 0xFE][..], &le].concat();
+
+        assert_eq!(detect(&le), UTF_16LE);
+        assert_eq!(detect(&be), UTF_16BE);
+        assert_eq!(detect(&TEXT.encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<u8>>()), UTF_16LE);
+        assert_eq!(decode(&with_bom, None), ("Plain text".to_string(), UTF_16LE));
+        assert_eq!(decode(&with_bom, Some(UTF_16LE)).0, "Plain text");
+        assert_eq!(binary(&le), None);
+    }
+
+    #[test]
+    fn detect_binary() {
+        assert_eq!(binary(TEXT.as_bytes()), None);
+        assert_eq!(binary(&WINDOWS_1251.encode(TEXT).0), None);
+        assert_eq!(binary(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some(Binary::NulBytes));
+        assert_eq!(binary(b"\x01\x02\x03 text \x04\x05"), Some(Binary::ControlCharacters));
+    }
+}
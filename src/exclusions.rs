@@ -0,0 +1,212 @@
+use std::{fmt, fs, io, path::{Path, PathBuf}};
+
+use clap::ValueEnum;
+use regex::Regex;
+
+use crate::lang::Language;
+
+/// Способ, которым запись списка исключений сравнивается со словом
+#[derive(Clone, Debug)]
+pub enum Pattern {
+    /// Слово исключается вместе со всеми формами, дающими ту же основу
+    Stemmed(String),
+    /// Исключается только эта форма слова
+    Exact(String),
+    /// Исключаются слова, подходящие под регулярное выражение
+    Regex(Regex),
+}
+
+/// Ошибка разбора строки файла исключений
+#[derive(Debug)]
+pub struct ParseError {
+    pub file: PathBuf,
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.file.display(), self.line, self.message)
+    }
+}
+
+/// Список исключений; каждая запись относится либо ко всем языкам, либо к одному.
+///
+/// Формат файла исключений:
+///
+/// ```text
+/// # комментарий
+/// слово            исключить слово со всеми формами
+/// =слово           исключить только эту форму
+/// re:^https?$      исключить слова, подходящие под регулярное выражение
+/// [ru]             последующие записи относятся только к русскому языку
+/// [*]              последующие записи снова относятся ко всем языкам
+/// include:other.txt  подключить другой файл (путь относительно текущего)
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Exclusions {
+    entries: Vec<(Option<Language>, Pattern)>,
+}
+
+impl Exclusions {
+    pub fn new() -> Self {
+        Self { entries: vec![] }
+    }
+
+    pub fn add(&mut self, lang: Option<Language>, pattern: Pattern) {
+        self.entries.push((lang, pattern));
+    }
+
+    /// Добавляет все записи `other`
+    pub fn append(&mut self, mut other: Exclusions) {
+        self.entries.append(&mut other.entries);
+    }
+
+    /// Записи, относящиеся к языку `lang`
+    pub fn for_language(&self, lang: Language) -> impl Iterator<Item = &Pattern> {
+        self.entries.iter()
+                    .filter(move |(l, _)| l.is_none_or(|l| l == lang))
+                    .map(|(_, pattern)| pattern)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Читает файл исключений вместе с подключёнными файлами. Ошибка возвращается,
+    /// только если не удалось прочитать сам `path`; ошибки в отдельных строках
+    /// собираются в список, а такие строки пропускаются.
+    pub fn load(path: &Path) -> io::Result<(Self, Vec<ParseError>)> {
+        let mut exclusions = Self::new();
+        let mut errors = vec![];
+        let text = fs::read_to_string(path)?;
+
+        exclusions.parse(&text, path, None, &mut vec![canonical(path)], &mut errors);
+
+        Ok((exclusions, errors))
+    }
+
+    /// Подключённый файл начинается в той же языковой секции, что и подключающий
+    fn parse(&mut self, text: &str, path: &Path, mut section: Option<Language>,
+             stack: &mut Vec<PathBuf>, errors: &mut Vec<ParseError>) {
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let error = |message: String| ParseError { file: path.to_path_buf(), line: i + 1, message };
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                match name.trim() {
+                    "*" => section = None,
+                    code => match Language::from_str(code, true) {
+                        Ok(lang) => section = Some(lang),
+                        Err(_) => errors.push(error(format!("неизвестный язык \"{code}\""))),
+                    },
+                }
+            } else if line.starts_with('[') {
+                errors.push(error(format!("незакрытая секция \"{line}\"")));
+            } else if let Some(include) = line.strip_prefix("include:") {
+                let include = path.parent().unwrap_or(Path::new("")).join(include.trim());
+                if stack.contains(&canonical(&include)) {
+                    errors.push(error(format!("циклическое подключение {}", include.display())));
+                    continue;
+                }
+
+                match fs::read_to_string(&include) {
+                    Ok(text) => {
+                        stack.push(canonical(&include));
+                        self.parse(&text, &include, section, stack, errors);
+                        stack.pop();
+                    }
+                    Err(e) => errors.push(error(format!("ошибка при открытии файла {}: {e}", include.display()))),
+                }
+            } else if let Some(pattern) = line.strip_prefix("re:") {
+                // An empty pattern matches every word and would empty the whole report
+                match pattern.trim() {
+                    "" => errors.push(error("пустое регулярное выражение".to_string())),
+                    pattern => match Regex::new(pattern) {
+                        Ok(re) => self.add(section, Pattern::Regex(re)),
+                        Err(e) => errors.push(error(format!("некорректное регулярное выражение: {e}"))),
+                    },
+                }
+            } else if let Some(word) = line.strip_prefix('=') {
+                match parse_word(word) {
+                    Ok(word) => self.add(section, Pattern::Exact(word)),
+                    Err(message) => errors.push(error(message)),
+                }
+            } else {
+                match parse_word(line) {
+                    Ok(word) => self.add(section, Pattern::Stemmed(word)),
+                    Err(message) => errors.push(error(message)),
+                }
+            }
+        }
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn parse_word(word: &str) -> Result<String, String> {
+    let word = word.trim();
+
+    if word.is_empty() {
+        Err("пустое слово".to_string())
+    } else if word.contains(char::is_whitespace) {
+        Err(format!("ожидалось одно слово, получено \"{word}\""))
+    } else {
+        Ok(word.to_lowercase())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::lang::Language;
+    use super::{Exclusions, Pattern};
+
+    #[test]
+    fn parse_exclusion_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("exclude.txt");
+        fs::write(&main, "\
+# Общие слова
+
+Который
+=однако
+re:^https?$
+[ru]
+include:common.txt
+[xx]
+два слова
+re:(
+[*]
+include:missing.txt
+re:
+[ru
+").unwrap();
+        fs::write(dir.path().join("common.txt"), "сказать\ninclude:exclude.txt\n").unwrap();
+
+        let (exclusions, errors) = Exclusions::load(&main).unwrap();
+        let errors = errors.iter()
+                           .map(|e| (e.file.file_name().unwrap().to_str().unwrap(), e.line))
+                           .collect::<Vec<_>>();
+
+        assert_eq!(errors, vec![("common.txt", 2), ("exclude.txt", 8), ("exclude.txt", 9),
+                                ("exclude.txt", 10), ("exclude.txt", 12), ("exclude.txt", 13),
+                                ("exclude.txt", 14)]);
+        assert_eq!(exclusions.len(), 4);
+        assert_eq!(exclusions.for_language(Language::English).count(), 3);
+        assert!(matches!(exclusions.for_language(Language::Russian).last(),
+                         Some(Pattern::Stemmed(w)) if w == "сказать"));
+    }
+}
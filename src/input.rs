@@ -0,0 +1,197 @@
+use std::{collections::HashSet, io::{self, Read}, path::{Path, PathBuf}};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::{overrides::OverrideBuilder, WalkBuilder};
+
+/// Имя файла со списком исключаемых путей в формате .gitignore
+pub const IGNORE_FILENAME: &str = ".notestemignore";
+/// Путь, означающий стандартный ввод
+pub const STDIN_PATH: &str = "-";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Source {
+    /// Имя документа, под которым он попадает в выдачу
+    pub fn name(&self, stdin_name: &str) -> String {
+        match self {
+            Source::Stdin => stdin_name.to_string(),
+            Source::File(path) => path.display().to_string(),
+        }
+    }
+}
+
+/// Считывает весь стандартный ввод
+pub fn read_stdin() -> io::Result<Vec<u8>> {
+    let mut buf = vec![];
+    io::stdin().lock().read_to_end(&mut buf)?;
+
+    Ok(buf)
+}
+
+/// Подан ли текст на стандартный ввод: через канал или перенаправлением из файла.
+/// Терминал и устройства вроде `/dev/null` (так запускают из cron и CI) не в счёт.
+pub fn stdin_piped() -> bool {
+    #[cfg(unix)]
+    {
+        use std::{fs::File, os::{fd::AsFd, unix::fs::FileTypeExt}};
+
+        io::stdin().as_fd()
+                   .try_clone_to_owned()
+                   .and_then(|fd| File::from(fd).metadata())
+                   .is_ok_and(|m| m.file_type().is_fifo() || m.is_file())
+    }
+    #[cfg(not(unix))]
+    {
+        use std::io::IsTerminal;
+
+        !io::stdin().is_terminal()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct WalkOptions {
+    /// Шаблоны файлов, которые следует включить при обходе каталогов
+    pub include: Vec<String>,
+    /// Шаблоны путей, которые следует пропустить при обходе каталогов
+    pub exclude: Vec<String>,
+    /// Переходить ли по символическим ссылкам
+    pub follow_symlinks: bool,
+}
+
+/// Раскрывает каталоги в списке путей в отсортированный список файлов.
+/// Пути, не являющиеся каталогами, возвращаются как есть, чтобы ошибки
+/// их открытия были выведены при чтении; `-` означает стандартный ввод.
+/// Файл, указанный несколько раз (в том числе через каталог или другой путь),
+/// возвращается один раз — там, где встретился впервые.
+pub fn collect_sources(paths: &[String], options: &WalkOptions) -> Vec<Source> {
+    let mut files = vec![];
+    let include = build_globset(&options.include);
+
+    for p in paths {
+        if p == STDIN_PATH {
+            files.push(Source::Stdin);
+            continue;
+        }
+
+        let path = Path::new(p);
+        if !path.is_dir() {
+            files.push(Source::File(path.to_path_buf()));
+            continue;
+        }
+
+        // Whitelist overrides would take precedence over ignore files,
+        // so includes are matched separately after the walk
+        let mut overrides = OverrideBuilder::new(path);
+        for glob in &options.exclude {
+            if let Err(e) = overrides.add(&format!("!{glob}")) {
+                eprintln!("Некорректный шаблон пути {glob}: {e}");
+            }
+        }
+        let overrides = match overrides.build() {
+            Ok(o) => o,
+            Err(e) => {
+                eprintln!("Ошибка при разборе шаблонов путей: {e}");
+                continue;
+            }
+        };
+
+        let walker = WalkBuilder::new(path)
+                                 .overrides(overrides)
+                                 .follow_links(options.follow_symlinks)
+                                 .require_git(false)
+                                 .add_custom_ignore_filename(IGNORE_FILENAME)
+                                 .sort_by_file_name(|a, b| a.cmp(b))
+                                 .build();
+
+        for entry in walker {
+            match entry {
+                Ok(e) if e.file_type().is_some_and(|t| t.is_file()) => {
+                    let relative = e.path().strip_prefix(path).unwrap_or(e.path());
+                    if include.is_empty() || include.is_match(relative) {
+                        files.push(Source::File(e.into_path()));
+                    }
+                }
+                Ok(_) => (),
+                Err(e) => eprintln!("Ошибка при обходе каталога {}: {e}", path.display()),
+            }
+        }
+    }
+
+    // Paths that cannot be resolved, e.g. missing files, are compared as given
+    let mut seen = HashSet::new();
+    files.retain(|source| match source {
+        Source::Stdin => seen.insert(None),
+        Source::File(path) => seen.insert(Some(path.canonicalize().unwrap_or_else(|_| path.clone()))),
+    });
+
+    files
+}
+
+fn build_globset(globs: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        match Glob::new(glob) {
+            Ok(g) => { builder.add(g); }
+            Err(e) => eprintln!("Некорректный шаблон пути {glob}: {e}"),
+        }
+    }
+
+    builder.build().unwrap_or_else(|e| {
+        eprintln!("Ошибка при разборе шаблонов путей: {e}");
+        GlobSet::empty()
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{collect_sources, Source, WalkOptions, IGNORE_FILENAME, STDIN_PATH};
+
+    #[test]
+    fn walk_with_globs_and_ignore_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("notes/drafts")).unwrap();
+        fs::write(root.join("notes/a.md"), "").unwrap();
+        fs::write(root.join("notes/b.txt"), "").unwrap();
+        fs::write(root.join("notes/drafts/c.md"), "").unwrap();
+        fs::write(root.join("notes/drafts/d.md"), "").unwrap();
+        fs::write(root.join("notes/secret.md"), "").unwrap();
+        fs::write(root.join(".gitignore"), "secret.md\n").unwrap();
+        fs::write(root.join(IGNORE_FILENAME), "d.md\n").unwrap();
+
+        let options = WalkOptions {
+            include: vec!["*.md".to_string()],
+            exclude: vec![],
+            follow_symlinks: false,
+        };
+        let files = collect_sources(&[root.display().to_string()], &options);
+        let names = files.iter()
+                         .map(|f| match f {
+                             Source::File(path) => path.strip_prefix(root).unwrap().display().to_string(),
+                             Source::Stdin => STDIN_PATH.to_string(),
+                         })
+                         .collect::<Vec<String>>();
+
+        assert_eq!(names, vec!["notes/a.md", "notes/drafts/c.md"]);
+
+        let options = WalkOptions { exclude: vec!["drafts".to_string()], ..options };
+        let files = collect_sources(&[STDIN_PATH.to_string(), root.display().to_string()], &options);
+
+        assert_eq!(files, vec![Source::Stdin, Source::File(root.join("notes/a.md"))]);
+
+        let a = root.join("notes/a.md");
+        let paths = [root.join("notes").display().to_string(), a.display().to_string(),
+                     root.join("notes/drafts/../a.md").display().to_string(), STDIN_PATH.to_string(),
+                     STDIN_PATH.to_string()];
+        let files = collect_sources(&paths, &options);
+
+        assert_eq!(files, vec![Source::File(a), Source::Stdin]);
+    }
+}
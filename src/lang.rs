@@ -0,0 +1,313 @@
+use std::{cmp::Reverse, collections::HashSet};
+
+use clap::ValueEnum;
+use rust_stemmers::Algorithm;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::stopwords;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, ValueEnum)]
+pub enum Language {
+    #[value(name = "en")]
+    English,
+    #[value(name = "ru")]
+    Russian,
+    #[value(name = "de")]
+    German,
+    #[value(name = "fr")]
+    French,
+    #[value(name = "es")]
+    Spanish,
+    #[value(name = "it")]
+    Italian,
+    #[value(name = "pt")]
+    Portuguese,
+    #[value(name = "nl")]
+    Dutch,
+    #[value(name = "da")]
+    Danish,
+    #[value(name = "no")]
+    Norwegian,
+    #[value(name = "sv")]
+    Swedish,
+    #[value(name = "fi")]
+    Finnish,
+    #[value(name = "hu")]
+    Hungarian,
+    #[value(name = "ro")]
+    Romanian,
+    #[value(name = "tr")]
+    Turkish,
+    #[value(name = "el")]
+    Greek,
+    #[value(name = "ar")]
+    Arabic,
+    #[value(name = "ta")]
+    Tamil,
+}
+
+impl Language {
+    /// Все поддерживаемые языки в порядке приоритета по умолчанию
+    pub const ALL: [Language; 18] = [
+        Language::English, Language::Russian, Language::German, Language::French,
+        Language::Spanish, Language::Italian, Language::Portuguese, Language::Dutch,
+        Language::Danish, Language::Norwegian, Language::Swedish, Language::Finnish,
+        Language::Hungarian, Language::Romanian, Language::Turkish, Language::Greek,
+        Language::Arabic, Language::Tamil,
+    ];
+
+    pub fn algorithm(self) -> Algorithm {
+        match self {
+            Language::English => Algorithm::English,
+            Language::Russian => Algorithm::Russian,
+            Language::German => Algorithm::German,
+            Language::French => Algorithm::French,
+            Language::Spanish => Algorithm::Spanish,
+            Language::Italian => Algorithm::Italian,
+            Language::Portuguese => Algorithm::Portuguese,
+            Language::Dutch => Algorithm::Dutch,
+            Language::Danish => Algorithm::Danish,
+            Language::Norwegian => Algorithm::Norwegian,
+            Language::Swedish => Algorithm::Swedish,
+            Language::Finnish => Algorithm::Finnish,
+            Language::Hungarian => Algorithm::Hungarian,
+            Language::Romanian => Algorithm::Romanian,
+            Language::Turkish => Algorithm::Turkish,
+            Language::Greek => Algorithm::Greek,
+            Language::Arabic => Algorithm::Arabic,
+            Language::Tamil => Algorithm::Tamil,
+        }
+    }
+
+    /// Код языка по ISO 639-1, как он принимается в `--languages`
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Russian => "ru",
+            Language::German => "de",
+            Language::French => "fr",
+            Language::Spanish => "es",
+            Language::Italian => "it",
+            Language::Portuguese => "pt",
+            Language::Dutch => "nl",
+            Language::Danish => "da",
+            Language::Norwegian => "no",
+            Language::Swedish => "sv",
+            Language::Finnish => "fi",
+            Language::Hungarian => "hu",
+            Language::Romanian => "ro",
+            Language::Turkish => "tr",
+            Language::Greek => "el",
+            Language::Arabic => "ar",
+            Language::Tamil => "ta",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "Английский",
+            Language::Russian => "Русский",
+            Language::German => "Немецкий",
+            Language::French => "Французский",
+            Language::Spanish => "Испанский",
+            Language::Italian => "Итальянский",
+            Language::Portuguese => "Португальский",
+            Language::Dutch => "Нидерландский",
+            Language::Danish => "Датский",
+            Language::Norwegian => "Норвежский",
+            Language::Swedish => "Шведский",
+            Language::Finnish => "Финский",
+            Language::Hungarian => "Венгерский",
+            Language::Romanian => "Румынский",
+            Language::Turkish => "Турецкий",
+            Language::Greek => "Греческий",
+            Language::Arabic => "Арабский",
+            Language::Tamil => "Тамильский",
+        }
+    }
+
+    /// Буквы латиницы помимо a-z, допустимые в словах языка
+    fn latin_extra(self) -> Option<&'static str> {
+        match self {
+            Language::English => Some(""),
+            Language::German => Some("äöüß"),
+            Language::French => Some("àâæçéèêëîïôœùûüÿ"),
+            Language::Spanish => Some("áéíñóúü"),
+            Language::Italian => Some("àèéìíîòóùú"),
+            Language::Portuguese => Some("áâãàçéêíóôõú"),
+            Language::Dutch => Some("áéíóúèëïöü"),
+            Language::Danish => Some("æøåé"),
+            Language::Norwegian => Some("æøåéèêóòô"),
+            Language::Swedish => Some("åäöé"),
+            Language::Finnish => Some("äöåšž"),
+            Language::Hungarian => Some("áéíóöőúüű"),
+            Language::Romanian => Some("ăâîșțşţ"),
+            Language::Turkish => Some("çğıöşüâîû"),
+            Language::Russian | Language::Greek | Language::Arabic | Language::Tamil => None,
+        }
+    }
+
+    /// Может ли символ (в нижнем регистре) встретиться в слове этого языка
+    pub fn accepts(self, c: char) -> bool {
+        match self {
+            Language::Russian => matches!(c, 'а'..='я' | 'ё'),
+            Language::Greek => matches!(c, '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}'),
+            Language::Arabic => matches!(c, '\u{0600}'..='\u{06FF}' | '\u{0750}'..='\u{077F}'),
+            Language::Tamil => matches!(c, '\u{0B80}'..='\u{0BFF}'),
+            _ => c.is_ascii_lowercase() || self.latin_extra().is_some_and(|extra| extra.contains(c)),
+        }
+    }
+}
+
+impl Serialize for Language {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Language {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Language::from_str(&code, true).map_err(|_| de::Error::custom(format!("неизвестный язык \"{code}\"")))
+    }
+}
+
+/// Во сколько раз и минимум на сколько голосов язык должен опередить первый
+/// подходящий язык, чтобы слово было отнесено к нему
+pub const MARGIN: (usize, usize) = (2, 3);
+
+pub trait Detector {
+    /// Определяет язык слова; `None`, если ни один из активных языков не подходит
+    fn detect(&self, word: &str) -> Option<Language>;
+
+    /// Определяет языки всех слов входного файла. По умолчанию каждое слово
+    /// рассматривается отдельно через [`Detector::detect`].
+    fn detect_all(&self, words: &[String]) -> Vec<Option<Language>> {
+        words.iter().map(|word| self.detect(word)).collect()
+    }
+}
+
+/// Определяет язык по алфавиту: слову подходят активные языки, которым
+/// принадлежат все его буквы. Апострофы и дефисы не учитываются.
+///
+/// Отдельное слово относится к первому подходящему языку. В файле же за языки
+/// голосуют слова с буквами, которых нет в латинице a-z, и стоп-слова, и другой
+/// подходящий язык выбирается, только если он явно опережает первый (см. [`MARGIN`]).
+/// Так слова вроде `haus` в немецком тексте относятся к немецкому, а не к английскому.
+pub struct AlphabetDetector {
+    languages: Vec<Language>,
+    stopwords: Vec<HashSet<&'static str>>,
+}
+
+impl AlphabetDetector {
+    pub fn new(languages: Vec<Language>) -> Self {
+        let stopwords = languages.iter().map(|&lang| stopwords::words(lang).collect()).collect();
+        Self { languages, stopwords }
+    }
+
+    /// Индексы активных языков, которым принадлежат все буквы слова
+    fn candidates(&self, word: &str) -> Vec<usize> {
+        if !word.chars().any(char::is_alphabetic) {
+            return vec![];
+        }
+
+        (0..self.languages.len()).filter(|&i| word.chars()
+                                                   .filter(|c| c.is_alphabetic())
+                                                   .all(|c| self.languages[i].accepts(c)))
+                                 .collect()
+    }
+}
+
+impl Detector for AlphabetDetector {
+    fn detect(&self, word: &str) -> Option<Language> {
+        self.candidates(word).first().map(|&i| self.languages[i])
+    }
+
+    fn detect_all(&self, words: &[String]) -> Vec<Option<Language>> {
+        let candidates = words.iter().map(|word| self.candidates(word)).collect::<Vec<_>>();
+
+        let mut votes = vec![0usize; self.languages.len()];
+        for (word, candidates) in words.iter().zip(&candidates) {
+            // An all-ASCII word fits every Latin language, so only a stopword says something about it
+            let ascii = word.chars().filter(|c| c.is_alphabetic()).all(|c| c.is_ascii_lowercase());
+            for &i in candidates {
+                if !ascii || self.stopwords[i].contains(word.as_str()) {
+                    votes[i] += 1;
+                }
+            }
+        }
+
+        let (times, more) = MARGIN;
+        candidates.iter()
+                  .map(|candidates| {
+                      let &first = candidates.first()?;
+                      let best = candidates.iter()
+                                           .copied()
+                                           .max_by_key(|&i| (votes[i], Reverse(i)))
+                                           .filter(|&i| votes[i] >= votes[first] * times && votes[i] >= votes[first] + more)
+                                           .unwrap_or(first);
+                      Some(self.languages[best])
+                  })
+                  .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::Tokenizer;
+    use super::{AlphabetDetector, Detector, Language};
+
+    #[test]
+    fn detect_by_alphabet() {
+        let detector = AlphabetDetector::new(Language::ALL.to_vec());
+
+        assert_eq!(detector.detect("привет"), Some(Language::Russian));
+        assert_eq!(detector.detect("hello"), Some(Language::English));
+        assert_eq!(detector.detect("straße"), Some(Language::German));
+        assert_eq!(detector.detect("garçon"), Some(Language::French));
+        assert_eq!(detector.detect("mañana"), Some(Language::Spanish));
+        assert_eq!(detector.detect("λόγος"), Some(Language::Greek));
+        assert_eq!(detector.detect("їжак"), None);
+        assert_eq!(detector.detect("кто-то"), Some(Language::Russian));
+    }
+
+    #[test]
+    fn detect_respects_active_languages() {
+        let detector = AlphabetDetector::new(vec![Language::German, Language::Russian]);
+
+        assert_eq!(detector.detect("haus"), Some(Language::German));
+        assert_eq!(detector.detect("garçon"), None);
+    }
+
+    #[test]
+    fn detect_from_document() {
+        let detector = AlphabetDetector::new(Language::ALL.to_vec());
+        let words = Tokenizer::new().words("Das Haus und die Häuser. Der Mann und die Männer.");
+        let languages = detector.detect_all(&words);
+
+        for (word, lang) in words.iter().zip(languages) {
+            assert_eq!(lang, Some(Language::German), "{word}");
+        }
+
+        // Shared stopwords like "die" and "in" are not enough to outvote the first language
+        let words = Tokenizer::new().words("Players die often in games.\n\nPlayers rarely win.");
+        assert!(detector.detect_all(&words).iter().all(|&lang| lang == Some(Language::English)));
+
+        let words = Tokenizer::new().words("The house and the houses, дом и дома");
+        assert_eq!(detector.detect_all(&words), vec![
+            Some(Language::English), Some(Language::English), Some(Language::English),
+            Some(Language::English), Some(Language::English), Some(Language::Russian),
+            Some(Language::Russian), Some(Language::Russian),
+        ]);
+    }
+
+    #[test]
+    fn codes_match_cli_names() {
+        use clap::ValueEnum;
+
+        for lang in Language::ALL {
+            assert_eq!(lang.to_possible_value().unwrap().get_name(), lang.code());
+        }
+    }
+}
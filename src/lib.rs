@@ -0,0 +1,28 @@
+//! Поиск слов, повторяющихся в нескольких документах, с учётом их форм.
+//!
+//! ```
+//! use notestem::Analyzer;
+//!
+//! let mut analyzer = Analyzer::builder().filenum(1).length(3).build();
+//! analyzer.add_document("a.md", "Повторы, повторы");
+//! analyzer.add_document("b.md", "Ещё повтор");
+//!
+//! let report = analyzer.report();
+//! assert_eq!(report.words[0].word, "повторы");
+//! assert_eq!(report.words[0].count, 3);
+//! ```
+mod analyzer;
+pub mod context;
+pub mod dict;
+pub mod encoding;
+pub mod exclusions;
+pub mod input;
+pub mod lang;
+pub mod markup;
+pub mod normalizer;
+pub mod report;
+pub mod stopwords;
+pub mod tokenizer;
+
+pub use analyzer::{Analyzer, AnalyzerBuilder};
+pub use report::{Format, Report};
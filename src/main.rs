@@ -1,63 +1,35 @@
-#![allow(dead_code)]
-use std::{collections::{HashMap, HashSet}, fs::File, io::{BufRead, BufReader, Read}, path::Path};
+use std::{fs::File, io::{self, BufRead, BufReader, Read}, path::Path};
 
-use rust_stemmers::{Algorithm, Stemmer};
-use clap::{builder::Str, Parser};
-use colored::Colorize;
-use unicode_segmentation::{Graphemes, UnicodeSegmentation};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
 
-struct Entry {
-    amount: usize,
-    contained_in: HashSet<String>,
-}
-
-struct Dict {
-    hashmap: HashMap<String, Entry>,
-}
-
-impl Dict {
-    pub fn new() -> Self {
-        Self { hashmap: HashMap::new() }
-    }
-
-    pub fn add(&mut self, word: String, fname: String) -> usize {
-        if self.hashmap.contains_key(&word) {
-            let ptr = self.hashmap.get_mut(&word).unwrap();
-            ptr.amount += 1;
-            ptr.contained_in.insert(fname.to_string());
-
-            return ptr.amount;
-        } 
-        else {
-            let entry = Entry { amount: 1, contained_in: HashSet::from([fname.to_string()]) };
-            self.hashmap.insert(word, entry);
-
-            return 1;
-        } 
-    }
-
-    pub fn sort(self, words: usize, filenum: usize, length: usize) -> Vec<(String, usize, HashSet<String>)> {
-        let mut v = self.hashmap.iter()
-                    .map(|(word, entry)| (word.clone(), entry.amount, entry.contained_in.clone()) )
-                    .filter(|(w, n, f)| 
-                            f.len() > filenum && 
-                            *n >= words &&
-                            &w[..].graphemes(true).count() > &length)
-                    .collect::<Vec<(String, usize, HashSet<String>)>>();
-
-        v.sort_by(|a, b| b.1.cmp(&a.1));
-
-        v
-    }
-}
+use notestem::{
+    encoding::Binary,
+    exclusions::Exclusions,
+    input::{self, Source, WalkOptions},
+    lang::Language,
+    markup::{Markup, Segmentation},
+    normalizer::{Exact, Lemmatizer, NormalizerKind, SnowballStemmer},
+    Analyzer, Format,
+};
 
-fn stem_and_compare(stemmer: &Stemmer, str1: &str, strvec: &Vec<String>) -> bool {
-    strvec.iter().map(|word| stemmer.stem(word)).filter(|word| word == &stemmer.stem(str1)).count() > 0
-}
+mod config;
 
-#[derive(Parser)]
+/// Параметры командной строки. Значения по умолчанию можно задать в `notestem.toml`
+/// (ключи совпадают с длинными именами параметров), а наборы значений — в секциях
+/// `[profiles.ИМЯ]`, выбираемых через `--profile ИМЯ`.
+#[derive(Parser, Serialize, Deserialize)]
 #[command(version, about, long_about = None)]
+#[serde(rename_all = "kebab-case")]
 struct Cli {
+    #[command(subcommand)]
+    #[serde(skip)]
+    command: Option<Command>,
+    /// Профиль из файла конфигурации
+    #[arg(long, value_name = "NAME", global = true)]
+    #[serde(skip)]
+    profile: Option<String>,
     /// Наименьшее число повторов слова
     #[arg(short, long, default_value_t = 2)]
     words: usize,
@@ -70,111 +42,264 @@ struct Cli {
     /// Слова, которые следует исключить из выдачи
     #[arg(short, long)]
     exclude: Option<Vec<String>>,
-    /// Файл со списком слов для исключения из выдачи
+    /// Файл исключений: слова, `=точная_форма`, `re:регулярное_выражение`,
+    /// секции `[ru]`, `include:путь` и комментарии `#`
     #[arg(short='E', long)]
     exclude_file: Option<String>,
-    /// Входные файлы
+    /// Не исключать встроенные стоп-слова
+    #[arg(long)]
+    no_stopwords: bool,
+    /// Файл с дополнительными стоп-словами, по слову на строку
+    #[arg(long)]
+    stopwords_file: Option<String>,
+    /// Языки, слова которых учитываются (по умолчанию все). Слово, подходящее по алфавиту нескольким
+    /// языкам, относится к первому из них, если стоп-слова и буквы с диакритикой в файле явно не указывают на другой
+    #[arg(short='L', long, value_delimiter = ',')]
+    languages: Option<Vec<Language>>,
+    /// Способ приведения словоформ к общему ключу
+    #[arg(short='n', long, value_enum, default_value_t = NormalizerKind::Stem)]
+    normalizer: NormalizerKind,
+    /// Словарь форм в текстовом формате OpenCorpora (dict.opcorpora.txt) для `--normalizer lemma`
+    #[arg(long)]
+    lemma_dict: Option<String>,
+    /// Шаблоны файлов, которые следует включить при обходе каталогов (например, '*.md')
+    #[arg(long)]
+    include: Vec<String>,
+    /// Шаблоны путей, которые следует пропустить при обходе каталогов
+    #[arg(long)]
+    exclude_path: Vec<String>,
+    /// Переходить по символическим ссылкам при обходе каталогов
+    #[arg(long)]
+    follow_symlinks: bool,
+    /// Показать каждое вхождение в виде файл:строка:столбец с N словами контекста
+    #[arg(short='C', long, value_name = "N")]
+    context: Option<usize>,
+    /// Показать все встреченные формы каждого слова
+    #[arg(long)]
+    show_forms: bool,
+    /// Формат выдачи
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+    /// Разметка входных файлов; по умолчанию определяется по расширению, стандартный ввод считается простым текстом
+    #[arg(long, value_enum)]
+    input_format: Option<Markup>,
+    /// Кодировка текстовых файлов (например, windows-1251 или koi8-r); по умолчанию определяется автоматически
+    #[arg(long, value_name = "NAME")]
+    encoding: Option<String>,
+    /// На что делить каждый файл перед подсчётом: file, heading (разделы по заголовкам),
+    /// paragraph или lines:N (блоки по N строк); каждая часть считается отдельным документом
+    #[arg(long, value_name = "UNIT", default_value_t = Segmentation::File)]
+    unit: Segmentation,
+    /// Завершаться с ошибкой, если какой-либо файл пришлось пропустить (двоичный или нечитаемый)
+    #[arg(long)]
+    strict: bool,
+    /// Выводить подробности обработки файлов, например определённую кодировку
+    #[arg(short, long)]
+    verbose: bool,
+    /// Имя, под которым стандартный ввод попадает в выдачу
+    #[arg(long, default_value = "<stdin>")]
+    stdin_name: String,
+    /// Входные файлы и каталоги; `-` означает стандартный ввод
     filenames: Vec<String>,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Работа с файлом конфигурации
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Показать итоговые настройки с учётом файла конфигурации, профиля и параметров
+    Show,
+}
+
+/// Разбирает командную строку и накладывает на неё файл конфигурации
+fn parse_args() -> (Cli, Option<config::Config>) {
+    let command = Cli::command();
+    let matches = command.clone().get_matches();
+    let args = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    let Some(path) = config::find() else {
+        if let Some(name) = &args.profile {
+            eprintln!("--profile: Профиль \"{name}\" задан, но файл конфигурации {} не найден", config::CONFIG_FILENAME);
+            std::process::exit(1);
+        }
+        return (args, None);
+    };
+
+    let config = config::Config::load(&path).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    let mut merged = config.merge(&args, &command, &matches, args.profile.as_deref()).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    merged.command = args.command;
+    merged.profile = args.profile;
+
+    (merged, Some(config))
+}
 
 fn main() {
-    let ru_stemmer = Stemmer::create(Algorithm::Russian);
-    let en_stemmer = Stemmer::create(Algorithm::English);
-    let args = Cli::parse();
+    let (args, config) = parse_args();
+
+    if let Some(Command::Config(ConfigCommand::Show)) = args.command {
+        match &config {
+            Some(config) => println!("# Конфигурация: {}", config.path.display()),
+            None => println!("# Файл конфигурации не найден"),
+        }
+        if let Some(name) = &args.profile {
+            println!("# Профиль: {name}");
+        }
+        match toml::to_string(&args) {
+            Ok(text) => print!("{text}"),
+            Err(e) => eprintln!("Ошибка при выводе конфигурации: {e}"),
+        }
+        return;
+    }
+
+    let encoding = args.encoding.as_deref().map(|label| {
+        Encoding::for_label(label.trim().as_bytes()).unwrap_or_else(|| {
+            eprintln!("--encoding: Неизвестная кодировка \"{label}\"");
+            std::process::exit(1);
+        })
+    });
 
     let mut exclude: Vec<String> = vec![];
 
+    let mut exclusions = Exclusions::new();
+
     if let Some(filepath) = args.exclude_file {
         let exclude_filepath = Path::new(&filepath);
-        match File::open(exclude_filepath) {
-            Ok(f) => exclude.append(&mut BufReader::new(f).lines()
-                                                  .filter_map(|x| x.ok())
-                                                  .collect::<Vec<String>>()),
+        match Exclusions::load(exclude_filepath) {
+            Ok((loaded, errors)) => {
+                for e in errors {
+                    eprintln!("--exclude-file: {e}");
+                }
+                exclusions = loaded;
+            }
             Err(e) => eprintln!("--exclude-file: Ошибка при открытии файла {}: {e}", exclude_filepath.display()),
         };
     }
 
+    if let Some(filepath) = args.stopwords_file {
+        let stopwords_filepath = Path::new(&filepath);
+        match File::open(stopwords_filepath) {
+            Ok(f) => exclude.extend(BufReader::new(f).lines()
+                                                     .map_while(Result::ok)
+                                                     .map(|line| line.trim().to_string())
+                                                     .filter(|line| !line.is_empty())),
+            Err(e) => eprintln!("--stopwords-file: Ошибка при открытии файла {}: {e}", stopwords_filepath.display()),
+        };
+    }
+
     if let Some(mut exclude_entries) = args.exclude {
         exclude.append(&mut exclude_entries);
     }
 
-    let mut ru_dict = Dict::new();
-    let mut en_dict = Dict::new();
-
-    let mut unstemmed: HashMap<String, String> = HashMap::new();
-    
-    if args.filenames.len() > 0 {
-        for f in args.filenames {
-            let path = Path::new(&f);
-            let mut file = match File::open(path) {
-                Ok(f) => f,
-                Err(e) => {
-                    eprintln!("Ошибка при открытии файла {}: {e}", path.display()); 
-                    continue;
-                }
+    let mut builder = Analyzer::builder()
+                               .words(args.words)
+                               .filenum(args.filenum)
+                               .length(args.length)
+                               .exclude(exclude)
+                               .exclusions(exclusions)
+                               .stopwords(!args.no_stopwords);
+    if let Some(languages) = args.languages {
+        builder = builder.languages(languages);
+    }
+    if let Some(width) = args.context {
+        builder = builder.context(width);
+    }
+    match args.normalizer {
+        NormalizerKind::Stem => (),
+        NormalizerKind::Exact => builder = builder.normalizer(Box::new(Exact)),
+        NormalizerKind::Lemma => {
+            // Checked here rather than by clap, since the dictionary may come from the config file
+            let Some(path) = args.lemma_dict.as_deref().map(Path::new) else {
+                eprintln!("--normalizer lemma: Не задан словарь форм; укажите --lemma-dict или lemma-dict в {}",
+                          config::CONFIG_FILENAME);
+                std::process::exit(1);
             };
-
-            let mut buf = String::new();
-            match file.read_to_string(&mut buf) {
-                Ok(_) => (),
+            match Lemmatizer::open(path) {
+                Ok(lemmatizer) => {
+                    let lemmatizer = lemmatizer.with_fallback(Box::new(SnowballStemmer::new()));
+                    builder = builder.normalizer(Box::new(lemmatizer));
+                }
                 Err(e) => {
-                    eprintln!("Ошибка при чтении файла {}: {e}", path.display());
-                    continue;
+                    eprintln!("--lemma-dict: Ошибка при чтении словаря {}: {e}", path.display());
+                    std::process::exit(1);
                 }
+            }
+        }
+    }
+    let mut analyzer = builder.build();
+
+    let walk = WalkOptions {
+        include: args.include,
+        exclude: args.exclude_path,
+        follow_symlinks: args.follow_symlinks,
+    };
+    let mut sources = input::collect_sources(&args.filenames, &walk);
+    if args.filenames.is_empty() && input::stdin_piped() {
+        sources.push(Source::Stdin);
+    }
+
+    if !sources.is_empty() {
+        let mut skipped = vec![];
+        for source in sources {
+            let f = source.name(&args.stdin_name);
+            let (markup, bytes) = match &source {
+                Source::Stdin => (args.input_format.unwrap_or_default(),
+                                  input::read_stdin().map_err(|e| ("Ошибка при чтении стандартного ввода".to_string(), e))),
+                Source::File(path) => (args.input_format.unwrap_or_else(|| Markup::from_path(path)),
+                                       read_file(path)),
             };
+            let text = bytes.and_then(|buf| markup.read(&buf, encoding)
+                                                  .map_err(|e| (format!("Ошибка при чтении файла {f}"), e)));
 
-            let words = buf.to_lowercase()
-                           .chars()
-                           .filter(|c| 
-                                   c.is_alphabetic() || 
-                                   c.is_whitespace() && 
-                                   c != &'\n')
-                           .collect::<String>()
-                           .split(' ')
-                           .map(|word| word.to_string())
-                           .collect::<Vec<String>>();
-
-            for w in &words {
-                if !w.is_ascii() {
-                    let stem = ru_stemmer.stem(&w).to_string();
-
-                    if stem_and_compare(&ru_stemmer, w, &exclude) {
-                        continue;
+            let text = match text {
+                Ok(text) => text,
+                Err((message, e)) => {
+                    // Binary files are expected in mixed directories and only show up in the summary
+                    let binary = e.get_ref().is_some_and(|e| e.is::<Binary>());
+                    if !binary || args.strict {
+                        eprintln!("{message}: {e}");
                     }
-
-                    // Clone galore!
-                    // TODO refac
-                    ru_dict.add(stem.clone(), f.clone());
-                    if !unstemmed.contains_key(&stem) {
-                        unstemmed.insert(stem, w.clone());
+                    if args.strict {
+                        std::process::exit(1);
                     }
+                    skipped.push((f, e.to_string()));
+                    continue;
                 }
-            }
-
-            for w in &words {
-                if w.is_ascii() {
-                    let stem = en_stemmer.stem(&w).to_string();
-
-                    if stem_and_compare(&en_stemmer, w, &exclude) {
-                        continue;
-                    }
+            };
 
-                    en_dict.add(stem.clone(), f.clone());
-                    if !unstemmed.contains_key(&stem) {
-                        unstemmed.insert(stem, w.clone());
-                    }
+            if let Some(encoding) = text.encoding().filter(|_| args.verbose) {
+                eprintln!("{f}: кодировка {}", encoding.name());
+            }
+            // Books are split into chapters, each analyzed as a document of its own
+            let mut units = vec![];
+            for (part, text) in text.into_parts() {
+                for (unit, text) in text.segment(args.unit) {
+                    let name = [f.as_str(), &part, &unit].into_iter()
+                                                         .filter(|s| !s.is_empty())
+                                                         .collect::<Vec<_>>()
+                                                         .join("#");
+                    units.push((name, text));
                 }
             }
+            analyzer.add_file(units);
         }
 
-        for (word, amount, files) in ru_dict.sort(args.words, args.filenum, args.length) {
-            println!("{:-<60}", "-".bold());
-            println!("Слово \"{}\" или его форма встречается {} в следующих файлах: ",
-                     unstemmed[&word].bold().bright_green(),
-                     format!("{amount} раз").bold().yellow());
-            for file in files {
-                println!("{}", file.purple());
+        analyzer.report().print(args.format, args.show_forms);
+
+        if !skipped.is_empty() {
+            eprintln!("Пропущено файлов: {}", skipped.len());
+            for (name, reason) in skipped {
+                eprintln!("  {name}: {reason}");
             }
         }
     } else {
@@ -182,23 +307,11 @@ fn main() {
     }
 }
 
+/// Считывает файл целиком; ошибка сопровождается сообщением о том, что не удалось
+fn read_file(path: &Path) -> Result<Vec<u8>, (String, io::Error)> {
+    let mut file = File::open(path).map_err(|e| (format!("Ошибка при открытии файла {}", path.display()), e))?;
+    let mut buf = vec![];
+    file.read_to_end(&mut buf).map_err(|e| (format!("Ошибка при чтении файла {}", path.display()), e))?;
 
-#[cfg(test)]
-mod tests {
-    use crate::Dict;
-
-// #[test]
-//     fn sort_vec() {
-//         let mut st = Dict::new();
-//         st.add("foo", "a.txt");
-//         st.add("bar", "b.txt");
-//         st.add("foo", "c.txt");
-//
-//         assert_eq!(
-//             st.sort(), 
-//             vec![
-//                 (&"foo", 2, &vec!["a.txt".to_string(), "c.txt".to_string()]),
-//                 (&"bar", 1, &vec!["b.txt".to_string()]),
-//             ])
-//     }
+    Ok(buf)
 }
@@ -0,0 +1,132 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::{push_line, Extracted, URL};
+
+/// Ограничители блоков листинга, литерального текста, вставок без обработки и комментариев
+static SKIPPED_BLOCK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?:-{4,}|\.{4,}|\+{4,}|/{4,}|```)").unwrap());
+
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^:!?[\w-]+!?:").unwrap());
+
+static BLOCK_MACRO: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[\w-]+::\S*\[.*\]$").unwrap());
+
+static PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:=+\s+|\.|(?:NOTE|TIP|IMPORTANT|WARNING|CAUTION):\s+)").unwrap()
+});
+
+static INLINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&[
+        r"`[^`]+`",
+        r"\+\+\+.*?\+\+\+",
+        r"\+[^+\s][^+]*\+",
+        r"(?:pass|kbd|btn|menu|stem|latexmath|asciimath|image|icon):[^\[\s]*\[[^\]]*\]",
+        r"footnote:\[([^\]]*)\]",
+        r"(?:link|mailto|xref):[^\[\s]*\[([^\]]*)\]",
+        r"(?i:https?|ftp|irc)://[^\[\s]*\[([^\]]*)\]",
+        r"<<[^,>]*,\s*([^>]*)>>",
+        r"<<[^>]*>>",
+        r"\{[\w-]+\}",
+        r"\[\[[^\]]*\]\]",
+        r"\[[#.][^\]]*\]",
+        r"\*\*([^*]+)\*\*",
+        r"\*([^*\s][^*]*)\*",
+        r"__([^_]+)__",
+        r"_([^_\s][^_]*)_",
+        r"##([^#]+)##",
+        r"#([^#\s][^#]*)#",
+        URL,
+    ].join("|")).unwrap()
+});
+
+/// Оставляет текст заголовков, абзацев, списков, таблиц, цитат и примеров.
+/// Пропускаются листинги и литеральные блоки, комментарии, атрибуты документа,
+/// строки атрибутов блоков, блочные макросы вроде `image::` и встроенный код.
+pub fn extract(source: &str) -> Extracted {
+    let mut extracted = Extracted::new();
+    // Delimiter closing the skipped block we are in
+    let mut block: Option<&str> = None;
+    // An indented line at the start of a paragraph begins a literal paragraph
+    let mut literal = false;
+    let mut paragraph_start = true;
+
+    for (i, line) in source.lines().enumerate() {
+        let number = i + 1;
+        let trimmed = line.trim_end();
+
+        if let Some(delimiter) = block {
+            if trimmed == delimiter {
+                block = None;
+            }
+            continue;
+        }
+        if trimmed.is_empty() {
+            literal = false;
+            paragraph_start = true;
+            continue;
+        }
+        let first = std::mem::replace(&mut paragraph_start, false);
+        if literal || (first && line.starts_with(char::is_whitespace)) {
+            literal = true;
+            continue;
+        }
+
+        if SKIPPED_BLOCK.is_match(trimmed) {
+            block = Some(if trimmed.starts_with("```") { "```" } else { trimmed });
+            continue;
+        }
+        if trimmed.starts_with("//") || ATTRIBUTE.is_match(trimmed) || BLOCK_MACRO.is_match(trimmed) ||
+           (trimmed.starts_with('[') && trimmed.ends_with(']')) {
+            paragraph_start = true;
+            continue;
+        }
+
+        let start = PREFIX.find(trimmed).map_or(0, |m| m.end());
+        let offset = extracted.text().len();
+        push_line(&mut extracted, line, start..trimmed.len(), number, &INLINE);
+        if trimmed.starts_with('=') && start > 0 {
+            extracted.mark_heading(offset);
+        }
+    }
+
+    extracted
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::Tokenizer;
+    use super::extract;
+
+    const DOC: &str = "\
+= Заголовок документа
+:author: Иванов
+:toc:
+
+// комментарий
+Текст с `кодом`, *жирным* и https://example.com[описанием ссылки].
+
+[source,rust]
+----
+fn main() {}
+----
+
+ литеральный абзац
+
+NOTE: Важное замечание.
+
+image::picture.png[]
+";
+
+    #[test]
+    fn asciidoc_prose_only() {
+        let extracted = extract(DOC);
+        let tokens = Tokenizer::new().tokens(extracted.text());
+        let words = tokens.iter().map(|t| t.text).collect::<Vec<_>>();
+
+        assert_eq!(words, vec!["Заголовок", "документа", "Текст", "с", "жирным", "и", "описанием", "ссылки",
+                               "Важное", "замечание"]);
+        assert_eq!(extracted.locate(tokens[0].offset), (1, 3));
+        assert_eq!(extracted.locate(tokens[6].offset), (6, 49));
+    }
+}
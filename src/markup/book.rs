@@ -0,0 +1,234 @@
+use std::{borrow::Cow, collections::HashMap, io::{self, Read}, sync::LazyLock};
+
+use encoding_rs::Encoding;
+use regex::bytes::Regex;
+use roxmltree::Node;
+
+use super::{html, invalid_data, open_archive, parse_xml, read_entry, read_entry_bytes, Extracted, Unit};
+
+/// Кодировка из объявления XML: `<?xml version="1.0" encoding="windows-1251"?>`
+static DECLARED_ENCODING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*<\?xml[^>]*encoding=["']([\w.:-]+)["']"#).unwrap()
+});
+
+/// Книга EPUB: главами считаются документы из порядка чтения (`spine`), места
+/// указываются строками внутри XHTML-файла главы. Главу, которую не удалось
+/// прочитать, пропускаем с предупреждением, не отказываясь от всей книги.
+pub fn epub(bytes: &[u8]) -> io::Result<Extracted> {
+    let mut archive = open_archive(bytes)?;
+
+    let container = read_entry(&mut archive, "META-INF/container.xml")?;
+    let container = parse_xml(&container)?;
+    let package_path = container.descendants()
+                                .find(|n| n.has_tag_name("rootfile"))
+                                .and_then(|n| n.attribute("full-path"))
+                                .ok_or_else(|| invalid_data("в EPUB не указан файл описания книги"))?;
+
+    let package = read_entry(&mut archive, package_path)?;
+    let package = parse_xml(&package)?;
+    let base = package_path.rsplit_once('/').map_or("", |(dir, _)| dir);
+    let manifest = package.descendants()
+                          .filter(|n| n.has_tag_name("item"))
+                          .filter_map(|n| Some((n.attribute("id")?, n.attribute("href")?)))
+                          .collect::<HashMap<&str, &str>>();
+    let spine = package.descendants()
+                       .filter(|n| n.has_tag_name("itemref"))
+                       .filter_map(|n| manifest.get(n.attribute("idref")?));
+
+    let mut extracted = Extracted::new();
+    for (i, href) in spine.enumerate() {
+        let href = percent_decode(href.split('#').next().unwrap_or_default());
+        let path = resolve(base, &href);
+
+        let chapter = match read_entry_bytes(&mut archive, &path) {
+            Ok(chapter) => chapter,
+            Err(e) => {
+                eprintln!("Глава {path} пропущена: {e}");
+                continue;
+            }
+        };
+        let (chapter, _) = crate::encoding::decode(&chapter, None);
+
+        extracted.start_part(format!("глава {}", i + 1));
+        extracted.append(html::extract_body(&chapter));
+    }
+
+    Ok(extracted)
+}
+
+/// Книга FictionBook, в том числе упакованная в zip. Главами считаются секции
+/// верхнего уровня основного тела книги, места указываются номерами абзацев
+/// внутри главы. Примечания и описание книги пропускаются.
+pub fn fb2(bytes: &[u8], encoding: Option<&'static Encoding>) -> io::Result<Extracted> {
+    let bytes = if bytes.starts_with(b"PK") { Cow::Owned(unzip_fb2(bytes)?) } else { Cow::Borrowed(bytes) };
+
+    // The prolog is matched as bytes: text in a legacy encoding may follow it right away
+    let declared = DECLARED_ENCODING.captures(&bytes[..bytes.len().min(200)])
+                                    .and_then(|caps| Encoding::for_label(&caps[1]));
+    let (xml, encoding) = crate::encoding::decode(&bytes, encoding.or(declared));
+    let document = parse_xml(&xml)?;
+
+    let body = document.descendants()
+                       .find(|n| n.has_tag_name("body") && n.attributes().all(|a| a.name() != "name"))
+                       .ok_or_else(|| invalid_data("в FB2 нет основного текста книги"))?;
+    let sections = body.children().filter(|n| n.has_tag_name("section")).collect::<Vec<_>>();
+    let chapters = if sections.is_empty() { vec![body] } else { sections };
+
+    let mut extracted = Extracted::new().with_unit(Unit::Paragraph);
+    extracted.encoding = Some(encoding);
+    for (i, chapter) in chapters.into_iter().enumerate() {
+        extracted.start_part(format!("глава {}", i + 1));
+
+        let paragraphs = chapter.descendants()
+                                .filter(|n| matches!(n.tag_name().name(), "p" | "v" | "subtitle" | "text-author"))
+                                .map(text)
+                                .filter(|text| !text.trim().is_empty());
+        for (number, paragraph) in paragraphs.enumerate() {
+            extracted.push(&paragraph, number + 1, 1);
+            extracted.separate();
+        }
+    }
+
+    Ok(extracted)
+}
+
+/// Путь файла архива по ссылке `href` из файла описания в каталоге `base`
+/// с раскрытыми `.` и `..`
+fn resolve(base: &str, href: &str) -> String {
+    let mut path = base.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>();
+    for segment in href.split('/') {
+        match segment {
+            "" | "." => (),
+            ".." => { path.pop(); }
+            segment => path.push(segment),
+        }
+    }
+
+    path.join("/")
+}
+
+/// Раскрывает `%XX` в ссылке: так записываются, например, кириллические имена файлов
+fn percent_decode(href: &str) -> String {
+    let bytes = href.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3)
+                       .and_then(|hex| std::str::from_utf8(hex).ok())
+                       .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Первый файл `.fb2` из архива `.fb2.zip`
+fn unzip_fb2(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut archive = open_archive(bytes)?;
+    let name = archive.file_names()
+                      .find(|name| name.to_lowercase().ends_with(".fb2"))
+                      .map(str::to_string)
+                      .ok_or_else(|| invalid_data("в архиве нет файла .fb2"))?;
+
+    let mut buf = vec![];
+    archive.by_name(&name).map_err(invalid_data)?.read_to_end(&mut buf)?;
+
+    Ok(buf)
+}
+
+fn text(node: Node) -> String {
+    node.descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect::<String>()
+        .replace('\n', " ")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::{WINDOWS_1251, WINDOWS_1252};
+
+    use crate::markup::tests::archive;
+    use super::{epub, fb2};
+
+    fn parts(extracted: super::Extracted) -> Vec<(String, String)> {
+        extracted.into_parts()
+                 .into_iter()
+                 .map(|(name, part)| (name, part.text().trim().to_string()))
+                 .collect()
+    }
+
+    #[test]
+    fn epub_chapters() {
+        let container = br#"<container xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles><rootfile full-path="OEBPS/content.opf"/></rootfiles></container>"#;
+        let package = br#"<package xmlns="http://www.idpf.org/2007/opf">
+  <manifest><item id="c1" href="one.xhtml"/><item id="c2" href="text/two.xhtml"/>
+    <item id="c3" href="text/../%D1%82%D1%80%D0%B8.xhtml"/><item id="c4" href="missing.xhtml"/></manifest>
+  <spine><itemref idref="c2"/><itemref idref="c1"/><itemref idref="c4"/><itemref idref="c3"/></spine></package>"#;
+        let chapter = |text: &str| format!("<html><head><title>Книга</title></head><body><p>{text}</p></body></html>");
+        let book = archive(&[
+            ("META-INF/container.xml", container),
+            ("OEBPS/content.opf", package),
+            ("OEBPS/one.xhtml", chapter("Первая глава").as_bytes()),
+            ("OEBPS/text/two.xhtml", chapter("Вторая глава").as_bytes()),
+            ("OEBPS/три.xhtml", &WINDOWS_1251.encode(&chapter("Третья глава")).0),
+        ]);
+
+        assert_eq!(parts(epub(&book).unwrap()), vec![
+            ("глава 1".to_string(), "Вторая глава".to_string()),
+            ("глава 2".to_string(), "Первая глава".to_string()),
+            ("глава 4".to_string(), "Третья глава".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn fb2_chapters() {
+        let xml = r#"<?xml version="1.0" encoding="windows-1251"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0">
+  <description><title-info><annotation><p>Аннотация</p></annotation></title-info></description>
+  <body>
+    <title><p>Название книги</p></title>
+    <section><title><p>Глава первая</p></title><p>Текст <emphasis>первой</emphasis> главы.</p></section>
+    <section><p>Вторая</p><section><p>Вложенная секция</p></section></section>
+  </body>
+  <body name="notes"><section><p>Примечание</p></section></body>
+</FictionBook>"#;
+        let book = WINDOWS_1251.encode(xml).0;
+
+        let extracted = fb2(&book, None).unwrap();
+        assert_eq!(extracted.encoding(), Some(WINDOWS_1251));
+        assert_eq!(parts(extracted.clone()), vec![
+            ("глава 1".to_string(), "Глава первая Текст первой главы.".to_string()),
+            ("глава 2".to_string(), "Вторая Вложенная секция".to_string()),
+        ]);
+        let (_, second) = extracted.into_parts().remove(1);
+        assert_eq!(second.locate("Вторая ".len()), (2, 1));
+
+        let zipped = archive(&[("book.fb2", &book)]);
+        assert_eq!(parts(fb2(&zipped, None).unwrap()).len(), 2);
+    }
+
+    #[test]
+    fn fb2_declared_encoding() {
+        let xml = r#"<?xml version="1.0" encoding="windows-1252"?>
+<FictionBook><body><p>Die Häuser und die Männer grüßen.</p></body></FictionBook>"#;
+        let book = WINDOWS_1252.encode(xml).0;
+        let extracted = fb2(&book, None).unwrap();
+
+        assert_eq!(extracted.encoding(), Some(WINDOWS_1252));
+        assert_eq!(parts(extracted), vec![
+            ("глава 1".to_string(), "Die Häuser und die Männer grüßen.".to_string()),
+        ]);
+    }
+}
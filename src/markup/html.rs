@@ -0,0 +1,155 @@
+use std::{ops::Range, sync::LazyLock};
+
+use html_escape::decode_html_entities;
+use regex::Regex;
+
+use super::{Extracted, Lines};
+
+/// Элементы, текст внутри которых не является прозой
+const SKIPPED: [&str; 6] = ["script", "style", "code", "pre", "kbd", "samp"];
+
+/// Элементы, содержимое которых не разбирается как HTML
+const RAW_TEXT: [&str; 2] = ["script", "style"];
+
+/// Блочные элементы, границы которых разделяют абзацы, даже если они идут на соседних строках
+const BLOCKS: [&str; 27] = [
+    "p", "div", "li", "dt", "dd", "blockquote", "h1", "h2", "h3", "h4", "h5", "h6", "td", "th",
+    "caption", "figcaption", "section", "article", "aside", "header", "footer", "nav", "main",
+    "ul", "ol", "dl", "hr",
+];
+
+/// Заголовки, с которых начинаются разделы документа
+const HEADINGS: [&str; 6] = ["h1", "h2", "h3", "h4", "h5", "h6"];
+
+/// Строчные элементы не разделяют слова: `<b>под</b>черкнуть` — одно слово
+const INLINE: [&str; 23] = [
+    "a", "abbr", "b", "bdi", "bdo", "cite", "data", "del", "dfn", "em", "i", "ins", "mark",
+    "q", "s", "small", "span", "strong", "sub", "sup", "time", "u", "wbr",
+];
+
+static TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<(/?)([A-Za-z][\w:-]*)[^>]*?(/?)>|<!--.*?-->|<!\[CDATA\[.*?\]\]>|<[!?][^>]*>").unwrap()
+});
+
+static ENTITY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"&(?:#[0-9]+|#[xX][0-9a-fA-F]+|[A-Za-z][A-Za-z0-9]*);?").unwrap()
+});
+
+/// Оставляет текстовые узлы документа с раскрытыми сущностями. Пропускаются теги
+/// с атрибутами, комментарии, `<script>`, `<style>`, а также код в `<code>`, `<pre>`,
+/// `<kbd>` и `<samp>`.
+pub fn extract(source: &str) -> Extracted {
+    extract_from(source, 0)
+}
+
+/// То же, что [`extract`], но без `<head>` с заголовком страницы: в главах книг
+/// он обычно повторяет название книги
+pub fn extract_body(source: &str) -> Extracted {
+    let body = source.to_ascii_lowercase().find("<body").unwrap_or(0);
+    extract_from(source, body)
+}
+
+fn extract_from(source: &str, start: usize) -> Extracted {
+    let lines = Lines::new(source);
+    let mut extracted = Extracted::new();
+    // Nesting depth of elements whose text is skipped
+    let mut skip = 0usize;
+    let mut text_start = start;
+    let mut pos = start;
+    // Where the text of the open heading starts
+    let mut heading = None;
+
+    while let Some(caps) = TAG.captures_at(source, pos) {
+        let tag = caps.get(0).unwrap();
+        if skip == 0 {
+            push_text(&mut extracted, &lines, source, text_start..tag.start());
+        }
+        pos = tag.end();
+        text_start = tag.end();
+
+        let Some(name) = caps.get(2) else {
+            // Comments, doctype, processing instructions and CDATA
+            extracted.separate();
+            continue;
+        };
+        let name = name.as_str().to_lowercase();
+        let closing = !caps[1].is_empty();
+        let self_closing = !caps[3].is_empty();
+
+        if BLOCKS.contains(&name.as_str()) {
+            extracted.mark_block();
+        } else if !INLINE.contains(&name.as_str()) {
+            extracted.separate();
+        }
+        if HEADINGS.contains(&name.as_str()) && !self_closing {
+            if !closing {
+                heading = Some(extracted.text().len());
+            } else if let Some(start) = heading.take() {
+                extracted.mark_heading(start);
+            }
+        }
+        if SKIPPED.contains(&name.as_str()) && !self_closing {
+            skip = if closing { skip.saturating_sub(1) } else { skip + 1 };
+        }
+        if RAW_TEXT.contains(&name.as_str()) && !closing && !self_closing {
+            // Script and style bodies may contain `<`, so jump straight to the closing tag
+            pos = source[pos..].to_ascii_lowercase()
+                               .find(&format!("</{name}"))
+                               .map_or(source.len(), |end| pos + end);
+            text_start = pos;
+        }
+    }
+    if skip == 0 {
+        push_text(&mut extracted, &lines, source, text_start..source.len());
+    }
+
+    extracted
+}
+
+/// Добавляет текстовый узел, раскрывая сущности вроде `&laquo;` и `&#1078;`
+fn push_text(extracted: &mut Extracted, lines: &Lines, source: &str, range: Range<usize>) {
+    let mut push = |text: &str, offset: usize| {
+        let (line, column) = lines.locate(offset);
+        extracted.push(text, line, column);
+    };
+
+    let mut last = range.start;
+    for entity in ENTITY.find_iter(&source[range.clone()]) {
+        let start = range.start + entity.start();
+        push(&source[last..start], last);
+        push(&decode_html_entities(entity.as_str()), start);
+        last = range.start + entity.end();
+    }
+    push(&source[last..range.end], last);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::Tokenizer;
+    use super::extract;
+
+    const DOC: &str = "\
+<!DOCTYPE html>
+<html><head><title>Заголовок</title>
+<style>p { color: red }</style>
+<script>if (a < b) { document.write(\"<p>скрипт</p>\") }</script></head>
+<body><!-- комментарий -->
+<p class=\"lead\">&laquo;Цитата&raquo; и caf&eacute; с <b>под</b>чёркиванием.</p>
+<p>Пример: <code>let x = 1;</code></p><pre>fn main() {}</pre>
+<p>Конец<br/>текста</p>
+</body></html>
+";
+
+    #[test]
+    fn html_text_nodes_only() {
+        let extracted = extract(DOC);
+        let tokens = Tokenizer::new().tokens(extracted.text());
+        let words = tokens.iter().map(|t| t.text).collect::<Vec<_>>();
+
+        assert_eq!(words, vec!["Заголовок", "Цитата", "и", "café", "с", "подчёркиванием", "Пример",
+                               "Конец", "текста"]);
+        assert_eq!(extracted.locate(tokens[2].offset), (6, 38));
+        assert_eq!(extracted.locate(tokens[5].offset), (6, 57));
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::LazyLock;
+
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+
+use super::{Extracted, Lines, URL};
+
+static BARE_URL: LazyLock<Regex> = LazyLock::new(|| Regex::new(URL).unwrap());
+
+/// Оставляет текст абзацев, заголовков, списков, таблиц и подписей к картинкам.
+/// Код, формулы, HTML, метаданные в начале файла и адреса ссылок пропускаются.
+pub fn extract(source: &str) -> Extracted {
+    let lines = Lines::new(source);
+    let mut extracted = Extracted::new();
+    let options = Options::ENABLE_TABLES |
+                  Options::ENABLE_FOOTNOTES |
+                  Options::ENABLE_STRIKETHROUGH |
+                  Options::ENABLE_TASKLISTS |
+                  Options::ENABLE_MATH |
+                  Options::ENABLE_YAML_STYLE_METADATA_BLOCKS |
+                  Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS;
+
+    // Nesting depth of elements whose text is not prose
+    let mut skip = 0usize;
+    // Whether each open link is an autolink, whose text is the address itself
+    let mut links = vec![];
+    // Where the text of the open heading starts
+    let mut heading = None;
+
+    for (event, range) in Parser::new_ext(source, options).into_offset_iter() {
+        match event {
+            Event::Start(Tag::CodeBlock(_) | Tag::MetadataBlock(_) | Tag::HtmlBlock) => {
+                extracted.separate();
+                skip += 1;
+            }
+            Event::End(TagEnd::CodeBlock | TagEnd::MetadataBlock(_) | TagEnd::HtmlBlock) => skip -= 1,
+            Event::Start(Tag::Link { link_type, .. }) => {
+                let autolink = matches!(link_type, LinkType::Autolink | LinkType::Email);
+                skip += usize::from(autolink);
+                links.push(autolink);
+            }
+            Event::End(TagEnd::Link) => {
+                skip -= usize::from(links.pop().unwrap_or_default());
+            }
+            Event::Start(Tag::Heading { .. }) => {
+                extracted.mark_block();
+                heading = Some(extracted.text().len());
+            }
+            // Lines of a list or a quote may follow each other without blank lines
+            Event::Start(Tag::Paragraph | Tag::Item | Tag::BlockQuote(_) | Tag::TableRow) => extracted.mark_block(),
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some(start) = heading.take() {
+                    extracted.mark_heading(start);
+                }
+                extracted.separate();
+            }
+            // Inline formatting may split a word, so it is not a word boundary
+            Event::Start(Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Superscript | Tag::Subscript) |
+            Event::End(TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Superscript | TagEnd::Subscript) => (),
+            Event::Text(text) if skip == 0 => {
+                // Escapes and entities make the text differ from the source
+                let exact = source.get(range.clone()) == Some(&*text);
+                let mut push = |piece: &str, at: usize| {
+                    let (line, column) = lines.locate(range.start + if exact { at } else { 0 });
+                    extracted.push(piece, line, column);
+                };
+
+                let mut last = 0;
+                for url in BARE_URL.find_iter(&text) {
+                    push(&text[last..url.start()], last);
+                    last = url.end();
+                }
+                push(&text[last..], last);
+            }
+            Event::Text(_) => (),
+            _ => extracted.separate(),
+        }
+    }
+
+    extracted
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::Tokenizer;
+    use super::extract;
+
+    const DOC: &str = "\
+---
+title: Черновик
+tags: [заметки]
+---
+
+# Заголовок
+
+Текст со `встроенным_кодом` и [ссылкой](https://example.com/путь),
+адрес https://example.org/страница, <https://autolink.net> и <b>тег</b>.
+
+```rust
+fn main() {}
+```
+
+Раз*деление* слова.
+";
+
+    #[test]
+    fn markdown_prose_only() {
+        let extracted = extract(DOC);
+        let tokens = Tokenizer::new().tokens(extracted.text());
+        let words = tokens.iter().map(|t| t.text).collect::<Vec<_>>();
+
+        assert_eq!(words, vec!["Заголовок", "Текст", "со", "и", "ссылкой", "адрес", "и", "тег",
+                               "Разделение", "слова"]);
+        assert_eq!(extracted.locate(tokens[0].offset), (6, 3));
+        assert_eq!(extracted.locate(tokens[4].offset), (8, 32));
+    }
+}
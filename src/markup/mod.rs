@@ -0,0 +1,628 @@
+use std::{collections::HashSet, fmt, io::{self, Cursor, Read}, ops::Range, path::Path, str::FromStr};
+
+use clap::ValueEnum;
+use encoding_rs::Encoding;
+use regex::Regex;
+use roxmltree::Document;
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::tokenizer::Tokenizer;
+
+mod asciidoc;
+mod book;
+mod html;
+mod markdown;
+mod office;
+mod org;
+mod pdf;
+mod rst;
+
+/// Адрес, записанный в тексте без разметки ссылки
+const URL: &str = r"\b(?i:[a-z][a-z0-9+.-]*://|www\.|mailto:)\S+";
+
+/// Разметка входного документа, определяющая, какой текст в нём считается прозой
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Markup {
+    /// Обычный текст: анализируется целиком
+    #[default]
+    Plain,
+    /// Markdown: пропускаются код, адреса ссылок, HTML и метаданные в начале файла
+    #[value(alias = "md")]
+    #[serde(alias = "md")]
+    Markdown,
+    /// Org-mode: пропускаются блоки исходного кода, ящики свойств и служебные строки
+    Org,
+    /// reStructuredText: пропускаются директивы, литеральные блоки и комментарии
+    Rst,
+    /// AsciiDoc: пропускаются листинги, атрибуты и блочные макросы
+    #[value(alias = "adoc")]
+    #[serde(alias = "adoc")]
+    Asciidoc,
+    /// HTML и XHTML: учитываются только текстовые узлы, кроме скриптов, стилей и кода
+    Html,
+    /// Документ Word (Office Open XML); места указываются номерами абзацев
+    Docx,
+    /// Текстовый документ OpenDocument; места указываются номерами абзацев
+    Odt,
+    /// Текстовый слой PDF; места указываются номерами страниц
+    Pdf,
+    /// Книга EPUB; каждая глава анализируется как отдельный документ
+    Epub,
+    /// Книга FictionBook (`.fb2` или `.fb2.zip`); каждая глава анализируется как отдельный документ
+    Fb2,
+}
+
+impl Markup {
+    /// Разметка по расширению файла; неизвестные расширения считаются обычным текстом
+    pub fn from_path(path: &Path) -> Self {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if name.to_lowercase().ends_with(".fb2.zip") {
+            return Markup::Fb2;
+        }
+
+        let extension = path.extension()
+                            .and_then(|e| e.to_str())
+                            .map(str::to_lowercase)
+                            .unwrap_or_default();
+
+        match extension.as_str() {
+            "md" | "markdown" | "mdown" | "mkd" | "mkdn" => Markup::Markdown,
+            "org" => Markup::Org,
+            "rst" | "rest" => Markup::Rst,
+            "adoc" | "asciidoc" | "asc" => Markup::Asciidoc,
+            "html" | "htm" | "xhtml" => Markup::Html,
+            "docx" => Markup::Docx,
+            "odt" => Markup::Odt,
+            "pdf" => Markup::Pdf,
+            "epub" => Markup::Epub,
+            "fb2" => Markup::Fb2,
+            _ => Markup::Plain,
+        }
+    }
+
+    /// Читает документ и извлекает из него текст, который следует анализировать.
+    /// Текстовые форматы декодируются из `encoding` или из кодировки, определённой
+    /// через [`encoding::detect`](crate::encoding::detect). Двоичные данные в текстовом
+    /// формате дают ошибку `InvalidData`, внутри которой лежит [`Binary`](crate::encoding::Binary).
+    pub fn read(self, bytes: &[u8], encoding: Option<&'static Encoding>) -> io::Result<Extracted> {
+        match self {
+            Markup::Docx => office::docx(bytes),
+            Markup::Odt => office::odt(bytes),
+            Markup::Pdf => pdf::extract(bytes),
+            Markup::Epub => book::epub(bytes),
+            Markup::Fb2 => book::fb2(bytes, encoding),
+            _ => {
+                // Text in UTF-16 is full of NUL bytes, so a forced UTF-16 skips the check
+                let utf16 = encoding.is_some_and(|e| e == encoding_rs::UTF_16LE || e == encoding_rs::UTF_16BE);
+                if let Some(reason) = crate::encoding::binary(bytes).filter(|_| !utf16) {
+                    return Err(invalid_data(reason));
+                }
+                let (source, encoding) = crate::encoding::decode(bytes, encoding);
+                let mut extracted = self.extract(&source);
+                extracted.encoding = Some(encoding);
+                Ok(extracted)
+            }
+        }
+    }
+
+    /// Извлекает текст из документа текстового формата. Двоичные форматы
+    /// читаются через [`Markup::read`], здесь же разбираются как обычный текст.
+    pub fn extract(self, source: &str) -> Extracted {
+        match self {
+            Markup::Plain | Markup::Docx | Markup::Odt | Markup::Pdf | Markup::Epub | Markup::Fb2 => Extracted::plain(source),
+            Markup::Markdown => markdown::extract(source),
+            Markup::Org => org::extract(source),
+            Markup::Rst => rst::extract(source),
+            Markup::Asciidoc => asciidoc::extract(source),
+            Markup::Html => html::extract(source),
+        }
+    }
+}
+
+/// Части, на которые делится каждый входной документ перед подсчётом; при подсчёте
+/// `--filenum` каждая часть считается отдельным документом
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Segmentation {
+    /// Документ целиком (книги всё равно делятся на главы)
+    #[default]
+    File,
+    /// Разделы от заголовка до следующего заголовка
+    Heading,
+    /// Абзацы; часть называется по месту своего начала: строке, абзацу или странице
+    Paragraph,
+    /// Блоки по N строк (в документах с абзацами или страницами — по N абзацев или страниц)
+    Lines(usize),
+}
+
+impl FromStr for Segmentation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "file" => Ok(Segmentation::File),
+            "heading" => Ok(Segmentation::Heading),
+            "paragraph" => Ok(Segmentation::Paragraph),
+            _ => match s.strip_prefix("lines:").and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => Ok(Segmentation::Lines(n)),
+                _ => Err(format!("ожидалось file, heading, paragraph или lines:N, получено \"{s}\"")),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Segmentation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Segmentation::File => write!(f, "file"),
+            Segmentation::Heading => write!(f, "heading"),
+            Segmentation::Paragraph => write!(f, "paragraph"),
+            Segmentation::Lines(n) => write!(f, "lines:{n}"),
+        }
+    }
+}
+
+impl TryFrom<String> for Segmentation {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Segmentation> for String {
+    fn from(segmentation: Segmentation) -> Self {
+        segmentation.to_string()
+    }
+}
+
+/// Единица, которой указывается место слова в исходном документе
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Unit {
+    /// Строка и столбец
+    #[default]
+    Line,
+    /// Номер абзаца и столбец внутри него
+    Paragraph,
+    /// Номер страницы и столбец внутри неё
+    Page,
+}
+
+/// Начало фрагмента извлечённого текста и его место в исходном документе
+#[derive(Clone, Copy, Debug)]
+struct Span {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+/// Начало именованной части документа: главы или раздела под заголовком
+#[derive(Clone, Debug)]
+struct Part {
+    offset: usize,
+    name: String,
+}
+
+/// Текст документа без разметки вместе с положением каждого фрагмента в исходнике
+#[derive(Clone, Debug, Default)]
+pub struct Extracted {
+    text: String,
+    spans: Vec<Span>,
+    unit: Unit,
+    encoding: Option<&'static Encoding>,
+    /// Главы, которые всегда анализируются как отдельные документы
+    parts: Vec<Part>,
+    headings: Vec<Part>,
+    /// Смещения начал блочных элементов, например `<p>` в HTML, которые разделяют абзацы
+    blocks: Vec<usize>,
+}
+
+impl Extracted {
+    pub fn new() -> Self {
+        Self { text: String::new(), spans: vec![], unit: Unit::Line, encoding: None, parts: vec![], headings: vec![],
+               blocks: vec![] }
+    }
+
+    /// Места фрагментов указываются в единицах `unit` вместо строк
+    pub fn with_unit(mut self, unit: Unit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Документ без разметки, текст которого совпадает с исходником
+    pub fn plain(text: &str) -> Self {
+        let mut extracted = Self::new();
+        extracted.push(text, 1, 1);
+        extracted
+    }
+
+    /// Добавляет фрагмент, который в исходнике начинается на строке `line` в столбце `column`
+    pub fn push(&mut self, text: &str, line: usize, column: usize) {
+        for (i, part) in text.split_inclusive('\n').enumerate() {
+            let column = if i == 0 { column } else { 1 };
+            self.spans.push(Span { offset: self.text.len(), line: line + i, column });
+            self.text.push_str(part);
+        }
+    }
+
+    /// Добавляет текст `other` с его местами, например очередную главу книги
+    pub fn append(&mut self, other: Extracted) {
+        self.separate();
+        let offset = self.text.len();
+        self.spans.extend(other.spans.into_iter().map(|s| Span { offset: s.offset + offset, ..s }));
+        self.parts.extend(other.parts.into_iter().map(|p| Part { offset: p.offset + offset, ..p }));
+        self.headings.extend(other.headings.into_iter().map(|h| Part { offset: h.offset + offset, ..h }));
+        self.blocks.extend(other.blocks.into_iter().map(|b| b + offset));
+        self.text.push_str(&other.text);
+    }
+
+    /// Отмечает текст, добавленный начиная со смещения `start`, как заголовок: с него
+    /// начинается раздел, названный этим текстом
+    pub fn mark_heading(&mut self, start: usize) {
+        let title = self.text[start..].split_whitespace().collect::<Vec<_>>().join(" ");
+        if !title.is_empty() {
+            self.headings.push(Part { offset: start, name: title });
+        }
+    }
+
+    /// Отмечает границу блочного элемента: следующий текст начинает новый абзац,
+    /// даже если в исходнике он идёт на соседней строке
+    pub fn mark_block(&mut self) {
+        self.separate();
+        if self.blocks.last() != Some(&self.text.len()) {
+            self.blocks.push(self.text.len());
+        }
+    }
+
+    /// Начинает часть документа с именем `name`, которая анализируется как отдельный
+    /// документ, например главу книги
+    pub fn start_part(&mut self, name: impl Into<String>) {
+        self.separate();
+        self.parts.push(Part { offset: self.text.len(), name: name.into() });
+    }
+
+    /// Делит документ на части, начатые через [`Extracted::start_part`]. Документ без
+    /// частей возвращается целиком с пустым именем; текст до первой части отбрасывается.
+    pub fn into_parts(self) -> Vec<(String, Extracted)> {
+        if self.parts.is_empty() {
+            return vec![(String::new(), self)];
+        }
+
+        self.split_at(&self.parts)
+            .into_iter()
+            .filter(|(name, _)| !name.is_empty())
+            .collect()
+    }
+
+    /// Делит документ на части согласно `segmentation`. Части без текста пропускаются;
+    /// текст до первого заголовка возвращается с пустым именем.
+    pub fn segment(self, segmentation: Segmentation) -> Vec<(String, Extracted)> {
+        let cuts = match segmentation {
+            Segmentation::File => return vec![(String::new(), self)],
+            Segmentation::Heading => self.headings.clone(),
+            Segmentation::Paragraph => {
+                // Lines without words in between, e.g. blank ones, end a paragraph
+                let gap = if self.unit == Unit::Line { 2 } else { 1 };
+                let block_between = |start: usize, end: usize| {
+                    let i = self.blocks.partition_point(|&b| b < start);
+                    self.blocks.get(i).is_some_and(|&b| b <= end)
+                };
+                let label = match self.unit {
+                    Unit::Line => "строка",
+                    Unit::Paragraph => "абзац",
+                    Unit::Page => "стр.",
+                };
+                self.cuts(|offset, line, previous| {
+                        previous.is_none_or(|(end, p)| line >= p + gap || block_between(end, offset))
+                    })
+                    .into_iter()
+                    .map(|offset| Part { offset, name: format!("{label} {}", self.locate(offset).0) })
+                    .collect()
+            }
+            Segmentation::Lines(n) => {
+                let label = match self.unit {
+                    Unit::Line => "строки",
+                    Unit::Paragraph => "абзацы",
+                    Unit::Page => "стр.",
+                };
+                let block = |line: usize| (line - 1) / n;
+                self.cuts(|_, line, previous| previous.is_none_or(|(_, p)| block(line) != block(p)))
+                    .into_iter()
+                    .map(|offset| {
+                        let first = block(self.locate(offset).0) * n + 1;
+                        Part { offset, name: format!("{label} {first}-{}", first + n - 1) }
+                    })
+                    .collect()
+            }
+        };
+
+        self.split_at(&cuts)
+    }
+
+    /// Смещения слов, перед которыми `cut(смещение слова, его строка, (конец, строка) предыдущего слова)`
+    /// велит начать новую часть
+    fn cuts(&self, cut: impl Fn(usize, usize, Option<(usize, usize)>) -> bool) -> Vec<usize> {
+        let mut previous = None;
+        Tokenizer::new().tokens(&self.text)
+                        .into_iter()
+                        .filter_map(|token| {
+                            let (line, _) = self.locate(token.offset);
+                            let start = cut(token.offset, line, previous);
+                            previous = Some((token.offset + token.text.len(), line));
+                            start.then_some(token.offset)
+                        })
+                        .collect()
+    }
+
+    /// Делит документ по `cuts`. Повторяющиеся имена частей, например одинаковые
+    /// заголовки, дополняются порядковым номером: `Пример`, `Пример (2)`.
+    fn split_at(&self, cuts: &[Part]) -> Vec<(String, Extracted)> {
+        let first = cuts.first().map_or(self.text.len(), |c| c.offset);
+        let ends = cuts.iter().skip(1).map(|c| c.offset).chain([self.text.len()]);
+        let mut used = HashSet::new();
+
+        std::iter::once((String::new(), self.slice(0..first)))
+            .chain(cuts.iter().zip(ends).map(|(cut, end)| (cut.name.clone(), self.slice(cut.offset..end))))
+            .filter(|(_, part)| !part.text.trim().is_empty())
+            .map(|(name, part)| {
+                let mut unique = name.clone();
+                for n in 2.. {
+                    if used.insert(unique.clone()) {
+                        break;
+                    }
+                    unique = format!("{name} ({n})");
+                }
+                (unique, part)
+            })
+            .collect()
+    }
+
+    fn slice(&self, range: Range<usize>) -> Extracted {
+        let (line, column) = self.locate(range.start);
+        let spans = std::iter::once(Span { offset: 0, line, column })
+                        .chain(self.spans.iter()
+                                         .filter(|s| s.offset > range.start && s.offset < range.end)
+                                         .map(|s| Span { offset: s.offset - range.start, ..*s }))
+                        .collect();
+        let headings = self.headings.iter()
+                                    .filter(|h| range.contains(&h.offset))
+                                    .map(|h| Part { offset: h.offset - range.start, name: h.name.clone() })
+                                    .collect();
+        let blocks = self.blocks.iter()
+                                .filter(|&b| range.contains(b))
+                                .map(|b| b - range.start)
+                                .collect();
+
+        Extracted {
+            text: self.text[range].to_string(),
+            spans,
+            unit: self.unit,
+            encoding: self.encoding,
+            parts: vec![],
+            headings,
+            blocks,
+        }
+    }
+
+    /// Отделяет следующий фрагмент от предыдущего, чтобы слова на их границе не склеились
+    pub fn separate(&mut self) {
+        if !self.text.is_empty() && !self.text.ends_with(char::is_whitespace) {
+            self.text.push(' ');
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// Кодировка, из которой был декодирован текстовый документ
+    pub fn encoding(&self) -> Option<&'static Encoding> {
+        self.encoding
+    }
+
+    /// Место в исходном документе (строка или иная [`Unit`] и столбец), соответствующее смещению `offset` в тексте
+    pub fn locate(&self, offset: usize) -> (usize, usize) {
+        let i = self.spans.partition_point(|s| s.offset <= offset);
+        let Some(span) = i.checked_sub(1).map(|i| self.spans[i]) else {
+            return (1, 1);
+        };
+
+        (span.line, span.column + self.text[span.offset..offset].chars().count())
+    }
+}
+
+impl From<&str> for Extracted {
+    fn from(text: &str) -> Self {
+        Self::plain(text)
+    }
+}
+
+impl From<String> for Extracted {
+    fn from(text: String) -> Self {
+        Self::plain(&text)
+    }
+}
+
+/// Добавляет часть `range` строки `line` с номером `number`, пропуская встроенную разметку.
+/// Совпадения `inline` отбрасываются; если в совпадении участвует группа, её текст остаётся.
+fn push_line(extracted: &mut Extracted, line: &str, range: Range<usize>, number: usize, inline: &Regex) {
+    let column = |offset: usize| line[..offset].chars().count() + 1;
+    let mut last = range.start;
+
+    for caps in inline.captures_iter(&line[range.clone()]) {
+        let m = caps.get(0).unwrap();
+        extracted.push(&line[last..range.start + m.start()], number, column(last));
+        extracted.separate();
+        if let Some(text) = caps.iter().skip(1).flatten().next() {
+            extracted.push(text.as_str(), number, column(range.start + text.start()));
+            extracted.separate();
+        }
+        last = range.start + m.end();
+    }
+    extracted.push(&line[last..range.end], number, column(last));
+    extracted.separate();
+}
+
+/// Открывает zip-архив, в котором хранится документ
+fn open_archive(bytes: &[u8]) -> io::Result<ZipArchive<Cursor<&[u8]>>> {
+    ZipArchive::new(Cursor::new(bytes)).map_err(invalid_data)
+}
+
+/// Считывает файл архива `name` в строку
+fn read_entry(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> io::Result<String> {
+    let mut text = String::new();
+    archive.by_name(name).map_err(invalid_data)?.read_to_string(&mut text)?;
+
+    Ok(text)
+}
+
+/// Считывает файл архива `name` без проверки кодировки
+fn read_entry_bytes(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> io::Result<Vec<u8>> {
+    let mut buf = vec![];
+    archive.by_name(name).map_err(invalid_data)?.read_to_end(&mut buf)?;
+
+    Ok(buf)
+}
+
+fn parse_xml(xml: &str) -> io::Result<Document<'_>> {
+    Document::parse(xml).map_err(invalid_data)
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Начала строк исходного документа для перевода смещений в строку и столбец
+struct Lines<'a> {
+    source: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(source: &'a str) -> Self {
+        let starts = std::iter::once(0)
+                               .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+                               .collect();
+
+        Self { source, starts }
+    }
+
+    fn locate(&self, offset: usize) -> (usize, usize) {
+        let line = self.starts.partition_point(|&s| s <= offset);
+        let column = self.source[self.starts[line - 1]..offset].chars().count() + 1;
+
+        (line, column)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::{io::{Cursor, Write}, path::Path};
+
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::{Extracted, Markup, Segmentation, Unit};
+
+    /// Zip-архив с файлами `files` для проверки форматов на его основе
+    pub(super) fn archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(vec![]));
+        for (name, content) in files {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn locate_plain_text() {
+        let extracted = Extracted::plain("Один два\nтри");
+
+        assert_eq!(extracted.locate(0), (1, 1));
+        assert_eq!(extracted.locate("Один ".len()), (1, 6));
+        assert_eq!(extracted.locate("Один два\n".len()), (2, 1));
+    }
+
+    #[test]
+    fn split_into_parts() {
+        let mut extracted = Extracted::new();
+        extracted.start_part("глава 1");
+        extracted.push("Первая\nглава", 1, 1);
+        extracted.start_part("глава 2");
+        extracted.push("Вторая глава", 1, 1);
+
+        let parts = extracted.into_parts();
+        let names = parts.iter().map(|(name, part)| (name.as_str(), part.text())).collect::<Vec<_>>();
+
+        assert_eq!(names, vec![("глава 1", "Первая\nглава "), ("глава 2", "Вторая глава")]);
+        assert_eq!(parts[0].1.locate("Первая\n".len()), (2, 1));
+        assert_eq!(parts[1].1.locate("Вторая ".len()), (1, 8));
+    }
+
+    #[test]
+    fn segment_documents() {
+        let names = |extracted: Extracted, segmentation: &str| {
+            extracted.segment(segmentation.parse().unwrap())
+                     .into_iter()
+                     .map(|(name, part)| (name, part.text().trim().to_string()))
+                     .collect::<Vec<_>>()
+        };
+        let text = "Первый абзац\nпродолжается.\n\nВторой абзац.\n\n\nТретий.";
+
+        assert_eq!(names(text.into(), "paragraph"), vec![
+            ("строка 1".to_string(), "Первый абзац\nпродолжается.".to_string()),
+            ("строка 4".to_string(), "Второй абзац.".to_string()),
+            ("строка 7".to_string(), "Третий.".to_string()),
+        ]);
+
+        let html = Markup::Html.extract("<p>Повторение первое.</p>\n<p>Повторение <b>второе</b>.</p>\n<ul><li>Пункт</li></ul>");
+        assert_eq!(names(html, "paragraph"), vec![
+            ("строка 1".to_string(), "Повторение первое.".to_string()),
+            ("строка 2".to_string(), "Повторение второе.".to_string()),
+            ("строка 3".to_string(), "Пункт".to_string()),
+        ]);
+
+        let list = Markup::Markdown.extract("# Список\n- первый пункт\n- второй пункт\n");
+        assert_eq!(names(list, "paragraph").iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(),
+                   vec!["строка 1", "строка 2", "строка 3"]);
+
+        let mut pages = Extracted::new().with_unit(Unit::Page);
+        pages.push("Первая страница.", 1, 1);
+        pages.separate();
+        pages.push("Третья страница.", 3, 1);
+        assert_eq!(names(pages, "paragraph").iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(),
+                   vec!["стр. 1", "стр. 3"]);
+        assert_eq!(names(text.into(), "lines:4").iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(),
+                   vec!["строки 1-4", "строки 5-8"]);
+
+        let markdown = Markup::Markdown.extract("Вступление.\n\n# Введение\n\nТекст.\n\n## Итоги\n\nКонец.\n");
+        assert_eq!(names(markdown, "heading"), vec![
+            (String::new(), "Вступление.".to_string()),
+            ("Введение".to_string(), "Введение Текст.".to_string()),
+            ("Итоги".to_string(), "Итоги Конец.".to_string()),
+        ]);
+
+        let repeated = Markup::Markdown.extract("# Пример\n\nПервый.\n\n# Пример\n\nВторой.\n\n# Пример (2)\n\nТретий.\n");
+        assert_eq!(names(repeated, "heading"), vec![
+            ("Пример".to_string(), "Пример Первый.".to_string()),
+            ("Пример (2)".to_string(), "Пример Второй.".to_string()),
+            ("Пример (2) (2)".to_string(), "Пример (2) Третий.".to_string()),
+        ]);
+        assert!("lines:0".parse::<Segmentation>().is_err());
+    }
+
+    #[test]
+    fn markup_by_extension() {
+        assert_eq!(Markup::from_path(Path::new("notes/draft.MD")), Markup::Markdown);
+        assert_eq!(Markup::from_path(Path::new("notes/draft.adoc")), Markup::Asciidoc);
+        assert_eq!(Markup::from_path(Path::new("books/novel.FB2.zip")), Markup::Fb2);
+        assert_eq!(Markup::from_path(Path::new("notes/draft.txt")), Markup::Plain);
+        assert_eq!(Markup::from_path(Path::new("README")), Markup::Plain);
+    }
+}
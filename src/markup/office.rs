@@ -0,0 +1,151 @@
+use std::io;
+
+use roxmltree::{Document, Node};
+
+use super::{open_archive, parse_xml, read_entry, Extracted, Unit};
+
+const WORD_NS: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+const TEXT_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:text:1.0";
+
+/// Абзацы документа Word (`word/document.xml`), включая таблицы и надписи
+pub fn docx(bytes: &[u8]) -> io::Result<Extracted> {
+    let xml = read_entry(&mut open_archive(bytes)?, "word/document.xml")?;
+    let document = parse_xml(&xml)?;
+
+    Ok(paragraphs(&document, |node| match node.tag_name().name() {
+        "p" if heading_style(node) => Part::Heading,
+        "p" => Part::Paragraph,
+        "t" => Part::Text,
+        "tab" | "br" | "cr" => Part::Space,
+        _ => Part::Other,
+    }, WORD_NS, false))
+}
+
+/// Абзацы и заголовки документа OpenDocument (`content.xml`) без сносок
+pub fn odt(bytes: &[u8]) -> io::Result<Extracted> {
+    let xml = read_entry(&mut open_archive(bytes)?, "content.xml")?;
+    let document = parse_xml(&xml)?;
+
+    Ok(paragraphs(&document, |node| match node.tag_name().name() {
+        "h" => Part::Heading,
+        "p" => Part::Paragraph,
+        "s" | "tab" | "line-break" => Part::Space,
+        "note" | "tracked-changes" => Part::Skipped,
+        _ => Part::Other,
+    }, TEXT_NS, true))
+}
+
+/// Абзац Word со стилем заголовка: `<w:pPr><w:pStyle w:val="Heading1"/></w:pPr>`
+fn heading_style(paragraph: Node) -> bool {
+    paragraph.children()
+             .filter(|n| n.has_tag_name((WORD_NS, "pPr")))
+             .flat_map(|n| n.children())
+             .filter(|n| n.has_tag_name((WORD_NS, "pStyle")))
+             .filter_map(|n| n.attribute((WORD_NS, "val")))
+             .any(|style| style.starts_with("Heading") || style == "Title")
+}
+
+/// Роль элемента разметки при сборе текста абзацев
+enum Part {
+    Paragraph,
+    /// Абзац-заголовок, с которого начинается раздел
+    Heading,
+    /// Элемент, текстовые узлы которого являются текстом абзаца
+    Text,
+    /// Пробел, табуляция или разрыв строки внутри абзаца
+    Space,
+    Skipped,
+    Other,
+}
+
+/// Собирает текст абзацев; местом в документе считается порядковый номер
+/// непустого абзаца. Вложенные абзацы, например в надписях, нумеруются отдельно.
+/// `bare_text` означает, что текстовые узлы прямо внутри абзаца тоже являются его текстом.
+fn paragraphs(document: &Document, part: fn(Node) -> Part, namespace: &str, bare_text: bool) -> Extracted {
+    let part = |node: Node| match node.tag_name().namespace() {
+        Some(ns) if ns == namespace => part(node),
+        _ => Part::Other,
+    };
+    let mut extracted = Extracted::new().with_unit(Unit::Paragraph);
+    let mut number = 0;
+
+    let skipped = |node: Node| node.ancestors().any(|a| matches!(part(a), Part::Skipped));
+    let paragraph = |node: Node| matches!(part(node), Part::Paragraph | Part::Heading);
+    for node in document.descendants().filter(|&n| paragraph(n) && !skipped(n)) {
+        let mut text = String::new();
+        collect(node, &part, bare_text, &mut text);
+
+        if !text.trim().is_empty() {
+            number += 1;
+            let start = extracted.text().len();
+            extracted.push(&text, number, 1);
+            if matches!(part(node), Part::Heading) {
+                extracted.mark_heading(start);
+            }
+            extracted.separate();
+        }
+    }
+
+    extracted
+}
+
+fn collect(node: Node, part: &impl Fn(Node) -> Part, in_text: bool, text: &mut String) {
+    for child in node.children() {
+        if child.is_text() {
+            if in_text {
+                text.push_str(&child.text().unwrap_or_default().replace('\n', " "));
+            }
+            continue;
+        }
+
+        match part(child) {
+            Part::Paragraph | Part::Heading | Part::Skipped => (),
+            Part::Space => text.push(' '),
+            Part::Text => collect(child, part, true, text),
+            Part::Other => collect(child, part, in_text, text),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{markup::{tests::archive, Unit}, tokenizer::Tokenizer};
+    use super::{docx, odt};
+
+    #[test]
+    fn docx_paragraphs() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:body>
+    <w:p><w:r><w:t>Первый</w:t></w:r><w:r><w:t xml:space="preserve"> абзац</w:t></w:r></w:p>
+    <w:p/>
+    <w:p><w:r><w:instrText>HYPERLINK</w:instrText><w:t>Второй</w:t><w:tab/><w:t>абзац</w:t></w:r></w:p>
+  </w:body>
+</w:document>"#;
+        let extracted = docx(&archive(&[("word/document.xml", xml.as_bytes())])).unwrap();
+        let tokens = Tokenizer::new().tokens(extracted.text());
+        let words = tokens.iter().map(|t| t.text).collect::<Vec<_>>();
+
+        assert_eq!(words, vec!["Первый", "абзац", "Второй", "абзац"]);
+        assert_eq!(extracted.unit(), Unit::Paragraph);
+        assert_eq!(extracted.locate(tokens[3].offset), (2, 8));
+    }
+
+    #[test]
+    fn odt_paragraphs() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+                         xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:text>
+    <text:h>Заголовок</text:h>
+    <text:p>Текст<text:s/>со <text:span>сноской</text:span><text:note><text:note-body><text:p>Сноска</text:p></text:note-body></text:note></text:p>
+  </office:text></office:body>
+</office:document-content>"#;
+        let extracted = odt(&archive(&[("content.xml", xml.as_bytes())])).unwrap();
+        let words = Tokenizer::new().words(extracted.text());
+
+        assert_eq!(words, vec!["заголовок", "текст", "со", "сноской"]);
+        assert!(odt(b"not a zip").is_err());
+    }
+}
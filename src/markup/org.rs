@@ -0,0 +1,121 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::{push_line, Extracted, URL};
+
+/// Блоки, содержимое которых не является прозой
+const SKIPPED_BLOCKS: [&str; 6] = ["src", "example", "export", "comment", "latex", "html"];
+
+static HEADLINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\*+\s+(?:(?:TODO|DONE)\s+)?(?:\[#\w\]\s+)?(.*?)(?:\s+:[\w@#%:]+:)?\s*$").unwrap()
+});
+
+static DRAWER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^:[\w-]+:\s*$").unwrap());
+
+static PLANNING: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?:SCHEDULED|DEADLINE|CLOSED):").unwrap());
+
+static INLINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&[
+        r"\[\[[^\]]*\]\[([^\]]*)\]\]",
+        r"\[\[[^\]]*\]\]",
+        r"\[fn:[^\]]*\]",
+        r"[<\[]\d{4}-\d{2}-\d{2}[^>\]]*[>\]]",
+        r"src_\w+(?:\[[^\]]*\])?\{[^}]*\}",
+        r"(?:^|[\s('\x22-])=\S(?:[^=]*?\S)?=",
+        r"(?:^|[\s('\x22-])~\S(?:[^~]*?\S)?~",
+        r"(?:^|[\s('\x22-])\*(\S(?:[^*]*?\S)?)\*",
+        r"(?:^|[\s('\x22-])/(\S(?:[^/]*?\S)?)/",
+        r"(?:^|[\s('\x22-])_(\S(?:[^_]*?\S)?)_",
+        r"(?:^|[\s('\x22-])\+(\S(?:[^+]*?\S)?)\+",
+        URL,
+    ].join("|")).unwrap()
+});
+
+/// Оставляет текст заголовков, абзацев, списков и таблиц. Пропускаются блоки кода
+/// и примеров, ящики свойств, ключевые слова `#+`, комментарии, строки планирования,
+/// теги заголовков, адреса ссылок, метки времени и моноширинный текст.
+pub fn extract(source: &str) -> Extracted {
+    let mut extracted = Extracted::new();
+    // Name of the skipped block we are in, e.g. "src"
+    let mut block: Option<&str> = None;
+    let mut drawer = false;
+
+    for (i, line) in source.lines().enumerate() {
+        let number = i + 1;
+        let trimmed = line.trim();
+        let lower = trimmed.to_lowercase();
+
+        if let Some(name) = block {
+            if lower.strip_prefix("#+end_").is_some_and(|end| end == name) {
+                block = None;
+            }
+            continue;
+        }
+        if drawer {
+            drawer = lower != ":end:";
+            continue;
+        }
+
+        if let Some(begin) = lower.strip_prefix("#+begin_") {
+            let name = begin.split_whitespace().next().unwrap_or_default();
+            block = SKIPPED_BLOCKS.iter().find(|&&b| b == name).copied();
+            continue;
+        }
+        if DRAWER.is_match(trimmed) && lower != ":end:" {
+            drawer = true;
+            continue;
+        }
+        if trimmed.starts_with("#+") || trimmed == "#" || trimmed.starts_with("# ") ||
+           trimmed == ":" || trimmed.starts_with(": ") || PLANNING.is_match(trimmed) {
+            continue;
+        }
+
+        let start = extracted.text().len();
+        match HEADLINE.captures(line) {
+            Some(caps) => {
+                push_line(&mut extracted, line, caps.get(1).unwrap().range(), number, &INLINE);
+                extracted.mark_heading(start);
+            }
+            None => push_line(&mut extracted, line, 0..line.len(), number, &INLINE),
+        }
+    }
+
+    extracted
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::Tokenizer;
+    use super::extract;
+
+    const DOC: &str = "\
+#+TITLE: Заметки
+* TODO [#A] Первый заголовок :работа:
+  :PROPERTIES:
+  :CUSTOM_ID: идентификатор
+  :END:
+  SCHEDULED: <2024-05-01 Wed>
+Текст с *жирным*, =кодом= и [[https://example.com][описанием ссылки]].
+# комментарий
+#+BEGIN_SRC rust
+fn main() {}
+#+END_SRC
+#+begin_quote
+Цитата остаётся.
+#+end_quote
+";
+
+    #[test]
+    fn org_prose_only() {
+        let extracted = extract(DOC);
+        let tokens = Tokenizer::new().tokens(extracted.text());
+        let words = tokens.iter().map(|t| t.text).collect::<Vec<_>>();
+
+        assert_eq!(words, vec!["Первый", "заголовок", "Текст", "с", "жирным", "и", "описанием", "ссылки",
+                               "Цитата", "остаётся"]);
+        assert_eq!(extracted.locate(tokens[0].offset), (2, 13));
+        assert_eq!(extracted.locate(tokens[4].offset), (7, 10));
+    }
+}
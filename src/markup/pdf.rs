@@ -0,0 +1,77 @@
+use std::{io, panic, sync::LazyLock};
+
+use regex::Regex;
+
+use super::{invalid_data, Extracted, Unit};
+
+/// Перенос слова в конце строки: `повто-\nрение`
+static HYPHENATION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\w)-\n(\w)").unwrap());
+
+/// Текстовый слой PDF по страницам; местом в документе считается номер страницы.
+/// Переносы слов в конце строк убираются.
+pub fn extract(bytes: &[u8]) -> io::Result<Extracted> {
+    // The extractor may panic on malformed documents instead of returning an error
+    let pages = panic::catch_unwind(|| pdf_extract::extract_text_from_mem_by_pages(bytes))
+                      .map_err(|_| invalid_data("не удалось разобрать PDF"))?
+                      .map_err(invalid_data)?;
+    let mut extracted = Extracted::new().with_unit(Unit::Page);
+
+    for (i, page) in pages.iter().enumerate() {
+        let text = HYPHENATION.replace_all(page, "$1$2").replace('\n', " ");
+        extracted.push(&text, i + 1, 1);
+        extracted.separate();
+    }
+
+    Ok(extracted)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{markup::Unit, tokenizer::Tokenizer};
+    use super::extract;
+
+    /// Минимальный PDF со стандартным шрифтом Helvetica, по строке текста на страницу
+    fn pdf(pages: &[&str]) -> Vec<u8> {
+        let n = pages.len();
+        let kids = (0..n).map(|i| format!("{} 0 R", 4 + 2 * i)).collect::<Vec<_>>().join(" ");
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!("<< /Type /Pages /Kids [{kids}] /Count {n} >>"),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_string(),
+        ];
+        for (i, text) in pages.iter().enumerate() {
+            let content = format!("BT /F1 12 Tf 72 720 Td ({text}) Tj ET");
+            objects.push(format!("<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+                                  /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>", 5 + 2 * i));
+            objects.push(format!("<< /Length {} >>\nstream\n{content}\nendstream", content.len()));
+        }
+
+        let mut out = "%PDF-1.4\n".to_string();
+        let mut offsets = vec![];
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out += &format!("{} 0 obj\n{object}\nendobj\n", i + 1);
+        }
+        let xref = out.len();
+        out += &format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            out += &format!("{offset:010} 00000 n \n");
+        }
+        out += &format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n", objects.len() + 1);
+
+        out.into_bytes()
+    }
+
+    #[test]
+    fn pdf_pages() {
+        let extracted = extract(&pdf(&["First page text", "Second page"])).unwrap();
+        let tokens = Tokenizer::new().tokens(extracted.text());
+        let words = tokens.iter().map(|t| t.text).collect::<Vec<_>>();
+
+        assert_eq!(words, vec!["First", "page", "text", "Second", "page"]);
+        assert_eq!(extracted.unit(), Unit::Page);
+        assert_eq!(extracted.locate(tokens[4].offset).0, 2);
+        assert!(extract(b"%PDF-1.4 broken").is_err());
+    }
+}
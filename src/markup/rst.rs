@@ -0,0 +1,161 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::{push_line, Extracted, URL};
+
+/// Директивы, аргумент и содержимое которых являются прозой; содержимое
+/// остальных директив пропускается
+const PROSE_DIRECTIVES: [&str; 17] = [
+    "admonition", "attention", "caution", "danger", "error", "hint", "important", "note", "tip", "warning",
+    "seealso", "topic", "sidebar", "rubric", "epigraph", "highlights", "pull-quote",
+];
+
+static DIRECTIVE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\.\.\s+([\w:+-]+)::(.*)$").unwrap());
+
+static ADORNMENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^(?:={2,}|-{2,}|`{2,}|:{2,}|'{2,}|"{2,}|~{2,}|\^{2,}|_{2,}|\*{2,}|\+{2,}|#{2,}|<{2,}|>{2,})$"#).unwrap()
+});
+
+static FIELD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^:\w[\w .-]*:(?:\s|$)").unwrap());
+
+static INLINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&[
+        r"``[^`]+``",
+        r":[\w.+-]+:`[^`]*`",
+        r"`[^`]*`:[\w.+-]+:",
+        r"`([^`<]*?)\s*<[^>]*>`__?",
+        r"`([^`]*)`__?",
+        r"`([^`]*)`",
+        r"\|[^|\s][^|]*\|_{0,2}",
+        r"\[[\w#*.-]*\]_",
+        r"\b([\w-]+)__?\b",
+        r"\*\*([^*]+)\*\*",
+        r"\*([^*\s][^*]*)\*",
+        URL,
+    ].join("|")).unwrap()
+});
+
+/// Оставляет текст абзацев, заголовков, списков, таблиц и директив-врезок вроде `note`.
+/// Пропускаются остальные директивы с их содержимым, литеральные блоки после `::`,
+/// комментарии, цели ссылок, списки полей, строки doctest и встроенный код.
+pub fn extract(source: &str) -> Extracted {
+    let mut extracted = Extracted::new();
+    // Lines indented deeper than this belong to a skipped block
+    let mut skip: Option<usize> = None;
+    // Indentation of a paragraph ending with `::`; a deeper indented block after it is literal
+    let mut literal: Option<usize> = None;
+    // Line number and text offset of the last prose line, which an adornment below turns into a title
+    let mut previous: Option<(usize, usize)> = None;
+
+    for (i, line) in source.lines().enumerate() {
+        let number = i + 1;
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim();
+
+        if let Some(base) = skip {
+            if trimmed.is_empty() || indent > base {
+                continue;
+            }
+            skip = None;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(base) = literal.take().filter(|&base| indent > base) {
+            skip = Some(base);
+            continue;
+        }
+
+        if let Some(caps) = DIRECTIVE.captures(trimmed) {
+            let name = caps[1].to_lowercase();
+            if PROSE_DIRECTIVES.contains(&name.as_str()) {
+                let argument = caps.get(2).unwrap().range();
+                push_line(&mut extracted, line, indent + argument.start..indent + argument.end, number, &INLINE);
+            } else {
+                skip = Some(indent);
+            }
+            continue;
+        }
+        // Comments, hyperlink targets, footnotes and substitution definitions
+        if trimmed == ".." || trimmed.starts_with(".. ") {
+            skip = Some(indent);
+            continue;
+        }
+        // An expanded literal block marker, which the adornment pattern would also match
+        if trimmed == "::" {
+            literal = Some(indent);
+            continue;
+        }
+        if ADORNMENT.is_match(trimmed) {
+            if let Some((_, start)) = previous.take().filter(|&(line, _)| line + 1 == number) {
+                extracted.mark_heading(start);
+            }
+            continue;
+        }
+        if trimmed.starts_with(">>>") || FIELD.is_match(trimmed) {
+            continue;
+        }
+
+        let end = match line.trim_end().strip_suffix("::") {
+            Some(text) => {
+                literal = Some(indent);
+                text.len()
+            }
+            None => line.len(),
+        };
+        previous = Some((number, extracted.text().len()));
+        push_line(&mut extracted, line, indent..end.max(indent), number, &INLINE);
+    }
+
+    extracted
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::Tokenizer;
+    use super::extract;
+
+    const DOC: &str = "\
+Заголовок
+=========
+
+:Автор: Иванов
+
+Текст с ``кодом``, :math:`x^2`, *выделением* и `ссылкой <https://example.com>`_.
+Пример::
+
+    fn main() {}
+
+.. code-block:: rust
+
+   let x = 1;
+
+.. note:: Важное замечание
+
+   Содержимое врезки.
+
+.. _цель:
+.. image:: picture.png
+   :width: 100px
+
+Последний абзац.
+
+::
+
+   литеральный текст
+";
+
+    #[test]
+    fn rst_prose_only() {
+        let extracted = extract(DOC);
+        let tokens = Tokenizer::new().tokens(extracted.text());
+        let words = tokens.iter().map(|t| t.text).collect::<Vec<_>>();
+
+        assert_eq!(words, vec!["Заголовок", "Текст", "с", "выделением", "и", "ссылкой", "Пример",
+                               "Важное", "замечание", "Содержимое", "врезки", "Последний", "абзац"]);
+        assert_eq!(extracted.locate(tokens[3].offset), (6, 34));
+        assert_eq!(extracted.locate(tokens[7].offset), (15, 11));
+    }
+}
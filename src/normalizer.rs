@@ -0,0 +1,162 @@
+use std::{collections::HashMap, fs::File, io::{self, BufRead, BufReader}, path::Path};
+
+use clap::ValueEnum;
+use rust_stemmers::Stemmer;
+use serde::{Deserialize, Serialize};
+
+use crate::lang::Language;
+
+/// Приводит словоформу к ключу, по которому формы одного слова объединяются
+pub trait Normalizer {
+    fn normalize(&self, word: &str, lang: Language) -> String;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NormalizerKind {
+    /// Стемминг алгоритмами Snowball
+    #[default]
+    Stem,
+    /// Лемматизация по словарю в формате OpenCorpora
+    Lemma,
+    /// Без нормализации: совпадают только одинаковые формы
+    Exact,
+}
+
+/// Стемминг алгоритмами Snowball для всех поддерживаемых языков
+pub struct SnowballStemmer {
+    stemmers: HashMap<Language, Stemmer>,
+}
+
+impl Default for SnowballStemmer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SnowballStemmer {
+    pub fn new() -> Self {
+        let stemmers = Language::ALL.iter()
+                                    .map(|&lang| (lang, Stemmer::create(lang.algorithm())))
+                                    .collect();
+
+        Self { stemmers }
+    }
+}
+
+impl Normalizer for SnowballStemmer {
+    fn normalize(&self, word: &str, lang: Language) -> String {
+        self.stemmers[&lang].stem(word).into_owned()
+    }
+}
+
+/// Оставляет слово без изменений
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Exact;
+
+impl Normalizer for Exact {
+    fn normalize(&self, word: &str, _lang: Language) -> String {
+        word.to_string()
+    }
+}
+
+/// Лемматизация по словарю: каждая известная форма заменяется начальной формой.
+/// Слова, которых нет в словаре, передаются запасному нормализатору.
+pub struct Lemmatizer {
+    lemmas: HashMap<String, String>,
+    fallback: Box<dyn Normalizer>,
+}
+
+impl Lemmatizer {
+    pub fn new(lemmas: HashMap<String, String>) -> Self {
+        Self { lemmas, fallback: Box::new(Exact) }
+    }
+
+    pub fn with_fallback(mut self, fallback: Box<dyn Normalizer>) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Self::from_opencorpora(BufReader::new(File::open(path)?))
+    }
+
+    /// Читает словарь в текстовом формате OpenCorpora (`dict.opcorpora.txt`):
+    /// блоки вида «номер леммы, затем строки `ФОРМА<TAB>граммемы`», разделённые
+    /// пустой строкой. Первая форма блока считается леммой. Если форма входит
+    /// в несколько лемм, используется первая из них.
+    pub fn from_opencorpora<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut lemmas = HashMap::new();
+        let mut current: Option<String> = None;
+
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim();
+
+            if line.is_empty() {
+                current = None;
+                continue;
+            }
+
+            let Some((form, _)) = line.split_once('\t') else {
+                // Lemma id line
+                current = None;
+                continue;
+            };
+            let form = form.to_lowercase();
+            let lemma = current.get_or_insert_with(|| form.clone());
+
+            lemmas.entry(form).or_insert_with(|| lemma.clone());
+        }
+
+        Ok(Self::new(lemmas))
+    }
+
+    pub fn len(&self) -> usize {
+        self.lemmas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lemmas.is_empty()
+    }
+}
+
+impl Normalizer for Lemmatizer {
+    fn normalize(&self, word: &str, lang: Language) -> String {
+        match self.lemmas.get(word) {
+            Some(lemma) => lemma.clone(),
+            None => self.fallback.normalize(word, lang),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::lang::Language;
+    use super::{Lemmatizer, Normalizer, SnowballStemmer};
+
+    const DICT: &str = "\
+1
+ЧЕЛОВЕК\tNOUN,anim,masc sing,nomn
+ЛЮДИ\tNOUN,anim,masc plur,nomn
+ЛЮДЕЙ\tNOUN,anim,masc plur,gent
+
+2
+ИДТИ\tINFN,impf,intr
+ШЁЛ\tVERB,impf,intr masc,sing,past,indc
+ШЛА\tVERB,impf,intr femn,sing,past,indc
+";
+
+    #[test]
+    fn lemmatize_suppletive_forms() {
+        let lemmatizer = Lemmatizer::from_opencorpora(DICT.as_bytes()).unwrap()
+                                    .with_fallback(Box::new(SnowballStemmer::new()));
+
+        assert_eq!(lemmatizer.len(), 6);
+        assert_eq!(lemmatizer.normalize("людей", Language::Russian), "человек");
+        assert_eq!(lemmatizer.normalize("шёл", Language::Russian), "идти");
+        assert_eq!(lemmatizer.normalize("шла", Language::Russian), "идти");
+        assert_eq!(lemmatizer.normalize("повторы", Language::Russian), "повтор");
+    }
+}
@@ -0,0 +1,245 @@
+use std::io;
+
+use clap::ValueEnum;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::{context::Context, dict::Entry, lang::Language, markup::Unit};
+
+/// Версия схемы машиночитаемой выдачи; увеличивается при несовместимых изменениях
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// Цветной отчёт для чтения в терминале
+    #[default]
+    Human,
+    /// JSON для редакторов и скриптов
+    Json,
+    /// CSV, по строке на каждую пару слова и файла
+    Csv,
+    /// То же, что CSV, но с табуляцией в качестве разделителя
+    Tsv,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub version: u32,
+    pub words: Vec<WordReport>,
+    #[serde(skip)]
+    pub(crate) context: Option<Context>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WordReport {
+    pub stem: String,
+    /// Самая частая словоформа, которой слово представлено в отчёте
+    pub word: String,
+    pub language: Language,
+    pub count: usize,
+    pub files: Vec<FileCount>,
+    /// Все встреченные словоформы по убыванию частоты
+    pub forms: Vec<FormCount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FormCount {
+    pub form: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileCount {
+    pub file: String,
+    pub count: usize,
+    /// Смещения вхождений в байтах от начала извлечённого из файла текста
+    #[serde(skip)]
+    pub positions: Vec<usize>,
+}
+
+impl Default for Report {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self { version: SCHEMA_VERSION, words: vec![], context: None }
+    }
+
+    /// Добавляет слово; файлы и формы упорядочиваются по убыванию числа повторов
+    pub fn add(&mut self, language: Language, stem: String, entry: &Entry) {
+        let mut files = entry.contained_in()
+                             .iter()
+                             .map(|(file, positions)| FileCount { file: file.clone(), count: positions.len(), positions: positions.clone() })
+                             .collect::<Vec<FileCount>>();
+        files.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.file.cmp(&b.file)));
+
+        let mut forms = entry.forms()
+                             .iter()
+                             .map(|(form, &count)| FormCount { form: form.clone(), count })
+                             .collect::<Vec<FormCount>>();
+        forms.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.form.cmp(&b.form)));
+        let word = forms.first().map(|f| f.form.clone()).unwrap_or_else(|| stem.clone());
+
+        self.words.push(WordReport { stem, word, language, count: entry.amount(), files, forms });
+    }
+
+    /// Тексты документов, если анализатор сохранял контекст вхождений
+    pub fn context(&self) -> Option<&Context> {
+        self.context.as_ref()
+    }
+
+    pub fn print(&self, format: Format, show_forms: bool) {
+        match format {
+            Format::Human => self.print_human(show_forms),
+            Format::Json => {
+                if let Err(e) = self.write_json(io::stdout().lock()) {
+                    eprintln!("Ошибка при выводе JSON: {e}");
+                }
+            }
+            Format::Csv | Format::Tsv => {
+                let delimiter = if format == Format::Csv { b',' } else { b'\t' };
+                if let Err(e) = self.write_delimited(io::stdout().lock(), delimiter) {
+                    eprintln!("Ошибка при выводе таблицы: {e}");
+                }
+            }
+        }
+    }
+
+    fn print_human(&self, show_forms: bool) {
+        let mut language = None;
+
+        for w in &self.words {
+            if language != Some(w.language) {
+                language = Some(w.language);
+                println!("{:=<60}", "=".bold());
+                println!("{}", format!("Язык: {}", w.language.name()).bold().cyan());
+            }
+
+            println!("{:-<60}", "-".bold());
+            println!("Слово \"{}\" или его форма встречается {} в следующих файлах: ",
+                     w.word.bold().bright_green(),
+                     format!("{} раз", w.count).bold().yellow());
+            if show_forms {
+                let forms = w.forms.iter()
+                                   .map(|f| format!("{} ({})", f.form, f.count))
+                                   .collect::<Vec<String>>()
+                                   .join(", ");
+                println!("Формы: {forms}");
+            }
+            for file in &w.files {
+                println!("{}: {}", file.file.purple(), format!("{} раз", file.count).yellow());
+
+                let Some(context) = &self.context else {
+                    continue;
+                };
+                for &position in &file.positions {
+                    if let Some(s) = context.snippet(&file.file, position) {
+                        let line = [s.before, s.word.bold().bright_green().to_string(), s.after]
+                                   .into_iter()
+                                   .filter(|part| !part.is_empty())
+                                   .collect::<Vec<String>>()
+                                   .join(" ");
+                        match s.unit {
+                            Unit::Line => println!("  {}:{}:{}: {line}", file.file.purple(), s.line, s.column),
+                            Unit::Paragraph => println!("  {}, абзац {}: {line}", file.file.purple(), s.line),
+                            Unit::Page => println!("  {}, стр. {}: {line}", file.file.purple(), s.line),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn write_json<W: io::Write>(&self, mut writer: W) -> serde_json::Result<()> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer).map_err(serde_json::Error::io)
+    }
+
+    /// Таблица с одной строкой на каждую пару слова и файла
+    pub fn write_delimited<W: io::Write>(&self, writer: W, delimiter: u8) -> csv::Result<()> {
+        let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_writer(writer);
+        writer.write_record(["stem", "word", "language", "total", "file", "count"])?;
+
+        for w in &self.words {
+            for file in &w.files {
+                writer.write_record([
+                    &w.stem,
+                    &w.word,
+                    w.language.code(),
+                    &w.count.to_string(),
+                    &file.file,
+                    &file.count.to_string(),
+                ])?;
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{dict::Dict, lang::Language};
+    use super::Report;
+
+    #[test]
+    fn json_schema() {
+        let mut dict = Dict::new();
+        dict.add("слов".to_string(), "слово".to_string(), "a.md".to_string(), 0);
+        dict.add("слов".to_string(), "слово".to_string(), "b.md".to_string(), 4);
+        dict.add("слов".to_string(), "слова".to_string(), "b.md".to_string(), 10);
+
+        let mut report = Report::new();
+        report.add(Language::Russian, "слов".to_string(), dict.get("слов").unwrap());
+
+        let mut out = vec![];
+        report.write_json(&mut out).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(value, serde_json::json!({
+            "version": 1,
+            "words": [{
+                "stem": "слов",
+                "word": "слово",
+                "language": "ru",
+                "count": 3,
+                "files": [
+                    { "file": "b.md", "count": 2 },
+                    { "file": "a.md", "count": 1 },
+                ],
+                "forms": [
+                    { "form": "слово", "count": 2 },
+                    { "form": "слова", "count": 1 },
+                ],
+            }],
+        }));
+    }
+
+    #[test]
+    fn delimited_rows() {
+        let mut dict = Dict::new();
+        dict.add("word".to_string(), "word".to_string(), "a, b.md".to_string(), 0);
+        dict.add("word".to_string(), "words".to_string(), "c.md".to_string(), 2);
+        dict.add("word".to_string(), "words".to_string(), "c.md".to_string(), 9);
+
+        let mut report = Report::new();
+        report.add(Language::English, "word".to_string(), dict.get("word").unwrap());
+
+        let mut out = vec![];
+        report.write_delimited(&mut out, b',').unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(),
+                   "stem,word,language,total,file,count\n\
+                    word,words,en,3,c.md,2\n\
+                    word,words,en,3,\"a, b.md\",1\n");
+
+        let mut out = vec![];
+        report.write_delimited(&mut out, b'\t').unwrap();
+        assert_eq!(String::from_utf8(out).unwrap().lines().nth(2), Some("word\twords\ten\t3\ta, b.md\t1"));
+    }
+}
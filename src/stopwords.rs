@@ -0,0 +1,49 @@
+use crate::lang::Language;
+
+/// Встроенный список стоп-слов языка, по слову на строку
+pub fn builtin(lang: Language) -> &'static str {
+    match lang {
+        Language::English => include_str!("../stopwords/en.txt"),
+        Language::Russian => include_str!("../stopwords/ru.txt"),
+        Language::German => include_str!("../stopwords/de.txt"),
+        Language::French => include_str!("../stopwords/fr.txt"),
+        Language::Spanish => include_str!("../stopwords/es.txt"),
+        Language::Italian => include_str!("../stopwords/it.txt"),
+        Language::Portuguese => include_str!("../stopwords/pt.txt"),
+        Language::Dutch => include_str!("../stopwords/nl.txt"),
+        Language::Danish => include_str!("../stopwords/da.txt"),
+        Language::Norwegian => include_str!("../stopwords/no.txt"),
+        Language::Swedish => include_str!("../stopwords/sv.txt"),
+        Language::Finnish => include_str!("../stopwords/fi.txt"),
+        Language::Hungarian => include_str!("../stopwords/hu.txt"),
+        Language::Romanian => include_str!("../stopwords/ro.txt"),
+        Language::Turkish => include_str!("../stopwords/tr.txt"),
+        Language::Greek => include_str!("../stopwords/el.txt"),
+        Language::Arabic => include_str!("../stopwords/ar.txt"),
+        Language::Tamil => include_str!("../stopwords/ta.txt"),
+    }
+}
+
+/// Стоп-слова языка без пустых строк
+pub fn words(lang: Language) -> impl Iterator<Item = &'static str> {
+    builtin(lang).lines()
+                 .map(str::trim)
+                 .filter(|line| !line.is_empty())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::lang::Language;
+    use super::words;
+
+    #[test]
+    fn every_language_has_lowercase_stopwords() {
+        for lang in Language::ALL {
+            assert!(words(lang).next().is_some(), "{lang:?}");
+            for word in words(lang) {
+                assert_eq!(word, word.to_lowercase(), "{lang:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// Слово в том виде, в котором оно встретилось в тексте
+    pub text: &'a str,
+    /// Смещение начала слова в байтах от начала текста
+    pub offset: usize,
+}
+
+impl Token<'_> {
+    /// Слово в нижнем регистре с типографским апострофом, заменённым на `'`
+    pub fn normalized(&self) -> String {
+        self.text.to_lowercase().replace('\u{2019}', "'")
+    }
+}
+
+/// Разбивает текст на слова по правилам UAX #29. Слова, соединённые дефисом
+/// без пробелов, считаются одним словом; числа и идентификаторы отбрасываются.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Tokenizer;
+
+impl Tokenizer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn tokens<'a>(&self, text: &'a str) -> Vec<Token<'a>> {
+        let mut tokens: Vec<Token<'a>> = vec![];
+        let mut segments = text.split_word_bound_indices().peekable();
+        // Whether the previous segment was a hyphen directly following the last token
+        let mut hyphen_after_last = false;
+
+        while let Some((offset, segment)) = segments.next() {
+            if is_hyphen(segment) {
+                hyphen_after_last = tokens.last()
+                                          .is_some_and(|t| t.offset + t.text.len() == offset) &&
+                                    segments.peek().is_some_and(|(_, next)| is_word(next));
+                continue;
+            }
+
+            if is_word(segment) {
+                match tokens.last_mut() {
+                    Some(last) if hyphen_after_last => {
+                        last.text = &text[last.offset..offset + segment.len()];
+                    }
+                    _ => tokens.push(Token { text: segment, offset }),
+                }
+            }
+
+            hyphen_after_last = false;
+        }
+
+        tokens
+    }
+
+    /// Слова текста в нормализованном виде (см. [`Token::normalized`])
+    pub fn words(&self, text: &str) -> Vec<String> {
+        self.tokens(text).iter().map(Token::normalized).collect()
+    }
+}
+
+fn is_hyphen(segment: &str) -> bool {
+    matches!(segment, "-" | "\u{2010}" | "\u{2011}")
+}
+
+fn is_word(segment: &str) -> bool {
+    segment.chars().any(char::is_alphabetic) &&
+    !segment.chars().any(|c| c.is_numeric() || c == '_')
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Tokenizer;
+
+    #[test]
+    fn split_across_lines_and_whitespace() {
+        assert_eq!(
+            Tokenizer::new().words("Первая строка\nвторая\tстрока,  и 42 числа!\r\n"),
+            vec!["первая", "строка", "вторая", "строка", "и", "числа"]);
+    }
+
+    #[test]
+    fn keep_compounds_and_apostrophes() {
+        assert_eq!(
+            Tokenizer::new().words("Don’t tell d'Artagnan about северо-западный подъезд - ok?"),
+            vec!["don't", "tell", "d'artagnan", "about", "северо-западный", "подъезд", "ok"]);
+    }
+
+    #[test]
+    fn token_offsets() {
+        let text = "раз, два-три";
+        let tokens = Tokenizer::new().tokens(text);
+
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(&text[tokens[1].offset..], "два-три");
+    }
+}